@@ -1,11 +1,21 @@
 pub mod tokens;
 
 use crate::references::bibliography::{BibEntry, BibReference, Bibliography};
-use crate::references::configuration::Configuration;
+use crate::references::configuration::keys::REVIEW_MODE;
+use crate::references::configuration::{ConfigRefEntry, Configuration};
+use crate::references::footnotes::{Footnote, FootnoteReference, Footnotes, ProcessFootnotes};
+use crate::references::formulas::ProcessFormulas;
+use crate::references::glossary::{
+    Glossary, GlossaryEntry, GlossaryList, GlossaryReference, ProcessGlossary,
+};
+use crate::references::index::{IndexBuilder, IndexList, IndexMarker};
+use crate::references::labels::{LabelKind, LabelReference, Labels, ProcessLabels};
 use crate::references::placeholders::ProcessPlaceholders;
 use crate::references::templates::{Template, TemplateVariable};
+use crate::utils::diagnostics::{Diagnostic, Diagnostics, D_INVALID_DECIMALS, D_UNRESOLVED_BIBREF};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, RwLock};
 
 pub const SECTION: &str = "section";
@@ -16,6 +26,9 @@ pub const CODE_BLOCK: &str = "code_block";
 pub const QUOTE: &str = "quote";
 pub const IMPORT: &str = "import";
 
+/// the maximum number of decimals numbers can be rounded to
+const MAX_DECIMALS: i64 = 20;
+
 #[derive(Clone, Debug)]
 pub enum MetadataValue {
     String(String),
@@ -41,8 +54,18 @@ pub enum Block {
     Table(Table),
     CodeBlock(CodeBlock),
     Quote(Quote),
+    Admonition(Admonition),
+    Details(Details),
+    Tabs(Tabs),
+    Columns(Columns),
+    Aligned(Aligned),
+    Raw(RawBlock),
     Import(Import),
     Placeholder(Arc<RwLock<Placeholder>>),
+    Math(MathBlock),
+    Figure(Figure),
+    GlossaryList(GlossaryList),
+    Index(IndexList),
 }
 
 #[derive(Clone, Debug)]
@@ -50,8 +73,24 @@ pub enum Line {
     Text(TextLine),
     Ruler(Ruler),
     Anchor(Anchor),
-    Centered(Centered),
     BibEntry(Arc<RwLock<BibEntry>>),
+    Footnote(Arc<RwLock<Footnote>>),
+    GlossaryEntry(Arc<RwLock<GlossaryEntry>>),
+}
+
+/// The location in the source an element was parsed from
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Span {
+    /// the path of the file the element is located in
+    pub path: Option<Arc<PathBuf>>,
+    /// the byte offset of the first character
+    pub start: usize,
+    /// the byte offset after the last character
+    pub end: usize,
+    /// the line of the first character starting at 1
+    pub line: usize,
+    /// the column of the first character starting at 1
+    pub column: usize,
 }
 
 #[derive(Clone, Debug)]
@@ -62,6 +101,15 @@ pub struct Document {
     pub(crate) placeholders: Vec<Arc<RwLock<Placeholder>>>,
     pub config: Configuration,
     pub bibliography: Bibliography,
+    pub labels: Labels,
+    pub footnotes: Footnotes,
+    pub(crate) footnote_list: Vec<Arc<RwLock<Footnote>>>,
+    pub(crate) annotations: Vec<Arc<RwLock<Annotation>>>,
+    pub(crate) tab_groups: Vec<Arc<AtomicUsize>>,
+    pub glossary: Glossary,
+    pub diagnostics: Diagnostics,
+    /// the lines of the imported files the snippets of diagnostics are taken from
+    pub(crate) sources: HashMap<PathBuf, Vec<String>>,
 }
 
 #[derive(Clone, Debug)]
@@ -69,6 +117,8 @@ pub struct Section {
     pub(crate) header: Header,
     pub(crate) elements: Vec<Block>,
     pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) footnotes: Vec<Arc<RwLock<Footnote>>>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -81,26 +131,52 @@ pub struct Header {
 #[derive(Clone, Debug)]
 pub struct Paragraph {
     pub(crate) elements: Vec<Line>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct List {
     pub(crate) ordered: bool,
+    pub(crate) style: ListStyle,
+    pub(crate) start: usize,
     pub items: Vec<ListItem>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct ListItem {
-    pub(crate) text: Line,
+    pub(crate) content: Vec<Block>,
     pub(crate) level: u16,
     pub(crate) ordered: bool,
+    pub(crate) style: ListStyle,
+    pub(crate) number: usize,
     pub(crate) children: Vec<ListItem>,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ListStyle {
+    Decimal,
+    LowerAlpha,
+    UpperAlpha,
+    LowerRoman,
+    UpperRoman,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alignment {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+
+#[derive(Clone, Debug, Default)]
 pub struct Table {
-    pub(crate) header: Row,
+    pub(crate) header: Vec<Row>,
     pub(crate) rows: Vec<Row>,
+    pub(crate) alignments: Vec<Option<Alignment>>,
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -110,25 +186,115 @@ pub struct Row {
 
 #[derive(Clone, Debug)]
 pub struct Cell {
-    pub(crate) text: Line,
+    pub(crate) content: Vec<Block>,
+    pub(crate) alignment: Option<Alignment>,
+    /// the index of the first column the cell is placed in
+    pub(crate) column: usize,
+    pub(crate) colspan: usize,
+    pub(crate) rowspan: usize,
+    /// if the cell is merged into the cell above it (^^)
+    pub(crate) merged: bool,
+    /// the formula the value of the cell is computed with
+    pub(crate) formula: Option<String>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct CodeBlock {
     pub(crate) language: String,
     pub(crate) code: String,
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct RawBlock {
+    pub(crate) format: String,
+    pub(crate) content: String,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct MathBlock {
+    pub(crate) expression: String,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Figure {
+    pub(crate) content: FigureContent,
+    pub(crate) caption: TextLine,
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) number: usize,
+    pub(crate) display: Option<ConfigRefEntry>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub enum FigureContent {
+    Image(Image),
+    Table(Table),
+    CodeBlock(CodeBlock),
 }
 
 #[derive(Clone, Debug)]
 pub struct Quote {
     pub(crate) metadata: Option<InlineMetadata>,
-    pub(crate) text: Vec<TextLine>,
+    pub(crate) content: Vec<Block>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Admonition {
+    pub(crate) kind: String,
+    pub(crate) title: Option<TextLine>,
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) content: Vec<Block>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Details {
+    pub(crate) summary: Option<TextLine>,
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) content: Vec<Block>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Tabs {
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) panes: Vec<TabPane>,
+    /// the number of the group of tabs in the document that is used for its default id
+    pub(crate) number: Arc<AtomicUsize>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct TabPane {
+    pub(crate) title: Option<TextLine>,
+    pub(crate) content: Vec<Block>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Columns {
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) columns: Vec<Vec<Block>>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Aligned {
+    pub(crate) alignment: Alignment,
+    pub(crate) content: Vec<Block>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct Import {
     pub(crate) path: String,
     pub(crate) anchor: Arc<RwLock<ImportAnchor>>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -142,11 +308,14 @@ pub struct InlineMetadata {
 }
 
 #[derive(Clone, Debug)]
-pub struct Ruler {}
+pub struct Ruler {
+    pub(crate) span: Span,
+}
 
 #[derive(Clone, Debug)]
 pub struct TextLine {
     pub subtext: Vec<Inline>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -166,58 +335,83 @@ pub enum Inline {
     Colored(Colored),
     BibReference(Arc<RwLock<BibReference>>),
     TemplateVar(Arc<RwLock<TemplateVariable>>),
+    Math(Math),
+    Reference(Arc<RwLock<LabelReference>>),
+    Footnote(Arc<RwLock<FootnoteReference>>),
+    GlossaryReference(Arc<RwLock<GlossaryReference>>),
+    IndexMarker(Arc<RwLock<IndexMarker>>),
+    Annotation(Arc<RwLock<Annotation>>),
 }
 
 #[derive(Clone, Debug)]
 pub struct PlainText {
     pub(crate) value: String,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct BoldText {
     pub(crate) value: Box<Inline>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct ItalicText {
     pub(crate) value: Box<Inline>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct UnderlinedText {
     pub(crate) value: Box<Inline>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct StrikedText {
     pub(crate) value: Box<Inline>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct MonospaceText {
     pub(crate) value: String,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct Math {
+    pub(crate) expression: String,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct SuperscriptText {
     pub(crate) value: Box<Inline>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct Checkbox {
     pub(crate) value: bool,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct Url {
     pub description: Option<String>,
     pub url: String,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct Image {
     pub(crate) url: Url,
     pub(crate) metadata: Option<InlineMetadata>,
+    /// the figure number of a labelled image outside of a figure or 0 if it isn't numbered
+    pub(crate) number: Arc<AtomicUsize>,
+    pub(crate) display: Option<ConfigRefEntry>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -225,29 +419,38 @@ pub struct Placeholder {
     pub(crate) name: String,
     pub(crate) value: Option<Element>,
     pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) span: Span,
 }
 
+/// A review note with an optional author and date that is only
+/// rendered when the document is rendered in review mode
 #[derive(Clone, Debug)]
-pub struct Anchor {
-    pub(crate) description: Box<Line>,
-    pub(crate) reference: String,
+pub struct Annotation {
+    pub(crate) text: String,
+    pub(crate) metadata: Option<InlineMetadata>,
+    pub(crate) visible: bool,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
-pub struct Centered {
-    pub(crate) line: TextLine,
+pub struct Anchor {
+    pub(crate) description: Box<Line>,
+    pub(crate) reference: String,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct Emoji {
     pub(crate) value: char,
     pub(crate) name: String,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
 pub struct Colored {
     pub(crate) value: Box<Inline>,
     pub(crate) color: String,
+    pub(crate) span: Span,
 }
 
 // implementations
@@ -261,6 +464,14 @@ impl Document {
             placeholders: Vec::new(),
             config: Configuration::default(),
             bibliography: Bibliography::new(),
+            labels: Labels::new(),
+            footnotes: Footnotes::new(),
+            footnote_list: Vec::new(),
+            annotations: Vec::new(),
+            tab_groups: Vec::new(),
+            glossary: Glossary::new(),
+            diagnostics: Diagnostics::new(),
+            sources: HashMap::new(),
         }
     }
 
@@ -272,6 +483,14 @@ impl Document {
         self.placeholders.push(placeholder);
     }
 
+    pub fn add_annotation(&mut self, annotation: Arc<RwLock<Annotation>>) {
+        self.annotations.push(annotation);
+    }
+
+    pub(crate) fn add_tab_group(&mut self, number: Arc<AtomicUsize>) {
+        self.tab_groups.push(number);
+    }
+
     pub fn create_toc(&self, ordered: bool) -> List {
         let mut list = List::new();
         list.ordered = ordered;
@@ -295,6 +514,29 @@ impl Document {
         list
     }
 
+    /// creates a list of all figures of the given kind with links to them
+    pub fn create_figure_list(&self, kind: LabelKind, ordered: bool) -> List {
+        let mut list = List::new();
+        list.ordered = ordered;
+        add_figures_to_list(&self.elements, kind, &mut list);
+
+        list
+    }
+
+    /// creates the alphabetical index linking to all index markers
+    pub fn create_index(&self) -> IndexList {
+        let mut builder = IndexBuilder::default();
+        self.elements.iter().for_each(|b| {
+            b.for_each_inline(&mut |inline| {
+                if let Inline::IndexMarker(marker) = inline {
+                    builder.add_marker(&marker.read().unwrap());
+                }
+            })
+        });
+
+        builder.build()
+    }
+
     /// Processes section and import elements
     ///
     /// if it encounters a section it checks if the sections is of smaller order than the previous one
@@ -326,8 +568,7 @@ impl Document {
                     let anchor = &mut arc_anchor.write().unwrap();
 
                     if let Some(doc) = &mut anchor.document {
-                        self.placeholders.append(&mut doc.placeholders);
-                        self.bibliography.combine(&mut doc.bibliography);
+                        self.combine_references(doc);
                         doc.elements.reverse();
                         self.elements.append(&mut doc.elements);
                         anchor.document = None;
@@ -352,22 +593,277 @@ impl Document {
         self.elements = new_order;
     }
 
+    /// moves the placeholders, references, definitions and diagnostics
+    /// of another document into this one
+    pub(crate) fn combine_references(&mut self, other: &mut Document) {
+        self.placeholders.append(&mut other.placeholders);
+        self.annotations.append(&mut other.annotations);
+        self.tab_groups.append(&mut other.tab_groups);
+        self.bibliography.combine(&mut other.bibliography);
+        self.labels.combine(&mut other.labels);
+        self.footnotes.combine(&mut other.footnotes);
+        self.glossary.combine(&mut other.glossary);
+        self.diagnostics.combine(&mut other.diagnostics);
+        self.sources.extend(other.sources.drain());
+    }
+
     pub fn post_process(&mut self) {
         self.postprocess_imports();
+        self.process_formulas();
         if self.is_root {
             self.process_definitions();
             self.bibliography.assign_entry_data();
+            self.process_labels();
+            self.process_footnotes();
+            self.process_glossary();
+            self.number_index_markers();
             self.process_placeholders();
+            self.process_annotations();
+            self.number_tab_groups();
+            self.add_unresolved_bibref_diagnostics();
+        }
+    }
+
+    /// shows all review annotations if the document is rendered in review mode
+    fn process_annotations(&mut self) {
+        let visible = self.is_review_mode();
+        self.annotations
+            .iter()
+            .for_each(|a| a.write().unwrap().visible = visible);
+    }
+
+    /// numbers all index markers in the order they appear in
+    /// so that they have an anchor with or without an index
+    fn number_index_markers(&mut self) {
+        let mut count = 0;
+        self.elements.iter().for_each(|b| {
+            b.for_each_inline(&mut |inline| {
+                if let Inline::IndexMarker(marker) = inline {
+                    count += 1;
+                    marker.write().unwrap().number = count;
+                }
+            })
+        });
+    }
+
+    /// numbers the groups of tabs of the document and its imports
+    /// so that their default ids are unique across all files
+    fn number_tab_groups(&mut self) {
+        self.tab_groups
+            .iter()
+            .enumerate()
+            .for_each(|(index, number)| number.store(index + 1, Ordering::Relaxed));
+    }
+
+    /// returns if the review annotations of the document are rendered
+    pub fn is_review_mode(&self) -> bool {
+        if let Some(entry) = self.config.get_entry(REVIEW_MODE) {
+            entry.get().as_string().to_lowercase() == "true"
+        } else {
+            false
+        }
+    }
+
+    /// adds a warning for every bibliography reference without a matching entry
+    fn add_unresolved_bibref_diagnostics(&mut self) {
+        for reference in self.bibliography.get_unresolved_references() {
+            let reference = reference.read().unwrap();
+            let mut diagnostic = Diagnostic::warning(
+                D_UNRESOLVED_BIBREF,
+                format!("unresolved bibliography reference '{}'", reference.key),
+                reference.span.clone(),
+            );
+            diagnostic.set_hint(&format!(
+                "define the entry with `[{}]: url` or `[{}]:[metadata]`",
+                reference.key, reference.key
+            ));
+            self.diagnostics.add(diagnostic);
         }
     }
 }
 
+impl Block {
+    /// Returns the span of the source the block was parsed from
+    pub fn get_span(&self) -> Span {
+        match self {
+            Block::Section(sec) => sec.span.clone(),
+            Block::Paragraph(par) => par.span.clone(),
+            Block::List(list) => list.span.clone(),
+            Block::Table(table) => table.span.clone(),
+            Block::CodeBlock(code) => code.span.clone(),
+            Block::Quote(quote) => quote.span.clone(),
+            Block::Admonition(admonition) => admonition.span.clone(),
+            Block::Details(details) => details.span.clone(),
+            Block::Tabs(tabs) => tabs.span.clone(),
+            Block::Columns(columns) => columns.span.clone(),
+            Block::Aligned(aligned) => aligned.span.clone(),
+            Block::Raw(raw) => raw.span.clone(),
+            Block::Import(import) => import.span.clone(),
+            Block::Placeholder(ph) => ph.read().unwrap().span.clone(),
+            Block::Math(math) => math.span.clone(),
+            Block::Figure(figure) => figure.span.clone(),
+            Block::GlossaryList(_) | Block::Index(_) => Span::default(),
+        }
+    }
+
+    /// calls the given function for every inline of the block in document order
+    pub(crate) fn for_each_inline(&self, f: &mut dyn FnMut(&Inline)) {
+        match self {
+            Block::Section(sec) => {
+                sec.header.line.for_each_inline(f);
+                sec.elements.iter().for_each(|e| e.for_each_inline(f));
+            }
+            Block::Paragraph(par) => par.elements.iter().for_each(|l| l.for_each_inline(f)),
+            Block::List(list) => list.items.iter().for_each(|i| i.for_each_inline(f)),
+            Block::Table(table) => table.for_each_inline(f),
+            Block::Quote(quote) => quote.content.iter().for_each(|b| b.for_each_inline(f)),
+            Block::Admonition(admonition) => {
+                if let Some(title) = &admonition.title {
+                    title.subtext.iter().for_each(|i| i.for_each_inline(f));
+                }
+                admonition.content.iter().for_each(|b| b.for_each_inline(f));
+            }
+            Block::Details(details) => {
+                if let Some(summary) = &details.summary {
+                    summary.subtext.iter().for_each(|i| i.for_each_inline(f));
+                }
+                details.content.iter().for_each(|b| b.for_each_inline(f));
+            }
+            Block::Tabs(tabs) => tabs.panes.iter().for_each(|pane| {
+                if let Some(title) = &pane.title {
+                    title.subtext.iter().for_each(|i| i.for_each_inline(f));
+                }
+                pane.content.iter().for_each(|b| b.for_each_inline(f));
+            }),
+            Block::Columns(columns) => columns
+                .columns
+                .iter()
+                .flatten()
+                .for_each(|b| b.for_each_inline(f)),
+            Block::Aligned(aligned) => aligned.content.iter().for_each(|b| b.for_each_inline(f)),
+            Block::Import(imp) => {
+                let anchor = imp.anchor.read().unwrap();
+                if let Some(doc) = &anchor.document {
+                    doc.elements.iter().for_each(|e| e.for_each_inline(f));
+                }
+            }
+            Block::Figure(figure) => {
+                if let FigureContent::Table(table) = &figure.content {
+                    table.for_each_inline(f);
+                }
+                figure
+                    .caption
+                    .subtext
+                    .iter()
+                    .for_each(|i| i.for_each_inline(f));
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Line {
+    /// Returns the span of the source the line was parsed from
+    pub fn get_span(&self) -> Span {
+        match self {
+            Line::Text(text) => text.span.clone(),
+            Line::Ruler(ruler) => ruler.span.clone(),
+            Line::Anchor(anchor) => anchor.span.clone(),
+            Line::BibEntry(bib) => bib.read().unwrap().span.clone(),
+            Line::Footnote(footnote) => footnote.read().unwrap().span.clone(),
+            Line::GlossaryEntry(entry) => entry.read().unwrap().span.clone(),
+        }
+    }
+
+    /// calls the given function for every inline of the line in document order
+    pub(crate) fn for_each_inline(&self, f: &mut dyn FnMut(&Inline)) {
+        match self {
+            Line::Text(text) => text.subtext.iter().for_each(|i| i.for_each_inline(f)),
+            Line::Anchor(anchor) => anchor.description.for_each_inline(f),
+            _ => {}
+        }
+    }
+}
+
+impl Inline {
+    /// Returns the span of the source the inline was parsed from
+    pub fn get_span(&self) -> Span {
+        match self {
+            Inline::Plain(plain) => plain.span.clone(),
+            Inline::Bold(bold) => bold.span.clone(),
+            Inline::Italic(italic) => italic.span.clone(),
+            Inline::Underlined(under) => under.span.clone(),
+            Inline::Striked(striked) => striked.span.clone(),
+            Inline::Monospace(mono) => mono.span.clone(),
+            Inline::Superscript(sup) => sup.span.clone(),
+            Inline::Url(url) => url.span.clone(),
+            Inline::Image(img) => img.span.clone(),
+            Inline::Placeholder(ph) => ph.read().unwrap().span.clone(),
+            Inline::Checkbox(checkbox) => checkbox.span.clone(),
+            Inline::Emoji(emoji) => emoji.span.clone(),
+            Inline::Colored(colored) => colored.span.clone(),
+            Inline::BibReference(bibref) => bibref.read().unwrap().span.clone(),
+            Inline::TemplateVar(var) => var.read().unwrap().span.clone(),
+            Inline::Math(math) => math.span.clone(),
+            Inline::Reference(reference) => reference.read().unwrap().span.clone(),
+            Inline::Footnote(footnote) => footnote.read().unwrap().span.clone(),
+            Inline::GlossaryReference(reference) => reference.read().unwrap().span.clone(),
+            Inline::IndexMarker(marker) => marker.read().unwrap().span.clone(),
+            Inline::Annotation(annotation) => annotation.read().unwrap().span.clone(),
+        }
+    }
+
+    /// calls the given function for the inline and all inlines nested in it
+    pub(crate) fn for_each_inline(&self, f: &mut dyn FnMut(&Inline)) {
+        f(self);
+        match self {
+            Inline::Bold(bold) => bold.value.for_each_inline(f),
+            Inline::Italic(italic) => italic.value.for_each_inline(f),
+            Inline::Underlined(under) => under.value.for_each_inline(f),
+            Inline::Striked(striked) => striked.value.for_each_inline(f),
+            Inline::Superscript(sup) => sup.value.for_each_inline(f),
+            Inline::Colored(colored) => colored.value.for_each_inline(f),
+            _ => {}
+        }
+    }
+}
+
+/// adds list items for the figures of a kind in the given blocks and nested sections
+fn add_figures_to_list(blocks: &[Block], kind: LabelKind, list: &mut List) {
+    blocks.iter().for_each(|b| match b {
+        Block::Figure(figure) if figure.get_kind() == kind => {
+            let mut text = TextLine::new();
+            text.add_subtext(Inline::Plain(PlainText::new(format!(
+                "{}: ",
+                figure.get_formatted_number()
+            ))));
+            text.subtext.append(&mut figure.caption.subtext.clone());
+            let anchor = Anchor {
+                description: Box::new(Line::Text(text)),
+                reference: figure.get_anchor(),
+                span: Span::default(),
+            };
+            list.add_item(ListItem::new(Line::Anchor(anchor), 1, list.ordered));
+        }
+        Block::Section(sec) => add_figures_to_list(&sec.elements, kind, list),
+        Block::Import(imp) => {
+            let anchor = imp.anchor.read().unwrap();
+            if let Some(doc) = &anchor.document {
+                add_figures_to_list(&doc.elements, kind, list)
+            }
+        }
+        _ => {}
+    });
+}
+
 impl Section {
     pub fn new(header: Header) -> Self {
         Self {
             header,
             elements: Vec::new(),
             metadata: None,
+            footnotes: Vec::new(),
+            span: Span::default(),
         }
     }
 
@@ -448,6 +944,16 @@ impl Header {
         Anchor {
             description: Box::new(self.line.clone()),
             reference: self.anchor.clone(),
+            span: Span::default(),
+        }
+    }
+}
+
+impl PlainText {
+    pub fn new(value: String) -> Self {
+        Self {
+            value,
+            span: Span::default(),
         }
     }
 }
@@ -456,6 +962,7 @@ impl Paragraph {
     pub fn new() -> Self {
         Self {
             elements: Vec::new(),
+            span: Span::default(),
         }
     }
 
@@ -468,7 +975,10 @@ impl List {
     pub fn new() -> Self {
         Self {
             ordered: false,
+            style: ListStyle::Decimal,
+            start: 1,
             items: Vec::new(),
+            span: Span::default(),
         }
     }
 
@@ -478,11 +988,16 @@ impl List {
 }
 
 impl ListItem {
+    /// creates a new list item with the line as its first paragraph
     pub fn new(text: Line, level: u16, ordered: bool) -> Self {
+        let mut paragraph = Paragraph::new();
+        paragraph.add_element(text);
         Self {
-            text,
+            content: vec![Block::Paragraph(paragraph)],
             level,
             ordered,
+            style: ListStyle::Decimal,
+            number: 1,
             children: Vec::new(),
         }
     }
@@ -490,12 +1005,33 @@ impl ListItem {
     pub fn add_child(&mut self, child: ListItem) {
         self.children.push(child)
     }
+
+    /// adds blocks that belong to the item
+    /// If `continued` is set, the lines of the first block are appended to
+    /// the first paragraph of the item if both of them are paragraphs
+    pub fn add_blocks(&mut self, blocks: Vec<Block>, continued: bool) {
+        let mut blocks = blocks.into_iter().peekable();
+        if let (true, Some(Block::Paragraph(first)), Some(Block::Paragraph(_))) =
+            (continued, self.content.first_mut(), blocks.peek())
+        {
+            if let Some(Block::Paragraph(mut paragraph)) = blocks.next() {
+                first.elements.append(&mut paragraph.elements);
+            }
+        }
+        self.content.extend(blocks)
+    }
+
+    pub(crate) fn for_each_inline(&self, f: &mut dyn FnMut(&Inline)) {
+        self.content.iter().for_each(|b| b.for_each_inline(f));
+        self.children.iter().for_each(|c| c.for_each_inline(f));
+    }
 }
 
 impl TextLine {
     pub fn new() -> Self {
         Self {
             subtext: Vec::new(),
+            span: Span::default(),
         }
     }
 
@@ -507,14 +1043,66 @@ impl TextLine {
 impl Table {
     pub fn new(header: Row) -> Self {
         Self {
-            header,
+            header: vec![header],
             rows: Vec::new(),
+            alignments: Vec::new(),
+            metadata: None,
+            span: Span::default(),
         }
     }
 
-    pub fn add_row(&mut self, row: Row) {
+    pub fn add_row(&mut self, mut row: Row) {
+        row.set_alignments(&self.alignments);
         self.rows.push(row)
     }
+
+    pub fn add_header_row(&mut self, mut row: Row) {
+        row.set_alignments(&self.alignments);
+        self.header.push(row)
+    }
+
+    /// sets the alignments of the columns and applies them to the existing rows
+    pub fn set_alignments(&mut self, alignments: Vec<Option<Alignment>>) {
+        self.header
+            .iter_mut()
+            .chain(self.rows.iter_mut())
+            .for_each(|r| r.set_alignments(&alignments));
+        self.alignments = alignments;
+    }
+
+    /// merges the cells marked with ^^ into the cells above them
+    /// by increasing their rowspan
+    pub(crate) fn merge_cells(&mut self) {
+        merge_rows(&mut self.header);
+        merge_rows(&mut self.rows);
+    }
+
+    pub(crate) fn for_each_inline(&self, f: &mut dyn FnMut(&Inline)) {
+        self.header
+            .iter()
+            .chain(self.rows.iter())
+            .flat_map(|r| r.cells.iter())
+            .flat_map(|c| c.content.iter())
+            .for_each(|b| b.for_each_inline(f))
+    }
+}
+
+/// increases the rowspan of the cells above merged cells and removes the merged cells
+fn merge_rows(rows: &mut [Row]) {
+    for index in 1..rows.len() {
+        let (above, below) = rows.split_at_mut(index);
+        for cell in below[0].cells.iter().filter(|c| c.merged) {
+            let target = above.iter_mut().rev().find_map(|row| {
+                row.cells
+                    .iter_mut()
+                    .find(|c| c.column == cell.column && !c.merged)
+            });
+            if let Some(target) = target {
+                target.rowspan += 1;
+            }
+        }
+    }
+    rows.iter_mut().for_each(|r| r.cells.retain(|c| !c.merged));
 }
 
 impl Row {
@@ -525,11 +1113,129 @@ impl Row {
     pub fn add_cell(&mut self, cell: Cell) {
         self.cells.push(cell)
     }
+
+    fn set_alignments(&mut self, alignments: &[Option<Alignment>]) {
+        self.cells
+            .iter_mut()
+            .for_each(|cell| cell.alignment = alignments.get(cell.column).copied().flatten())
+    }
+}
+
+impl Cell {
+    pub fn new(content: Vec<Block>) -> Self {
+        Self {
+            content,
+            alignment: None,
+            column: 0,
+            colspan: 1,
+            rowspan: 1,
+            merged: false,
+            formula: None,
+            span: Span::default(),
+        }
+    }
+}
+
+impl Alignment {
+    /// returns the alignment of a column of a table separator row
+    /// e.g. `:---` (left), `:---:` (center) and `---:` (right)
+    pub fn from_separator(column: &str) -> Option<Self> {
+        let column = column.trim();
+        match (
+            column.starts_with(':'),
+            column.ends_with(':') && column.len() > 1,
+        ) {
+            (true, true) => Some(Alignment::Center),
+            (true, false) => Some(Alignment::Left),
+            (false, true) => Some(Alignment::Right),
+            (false, false) => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Alignment::Left => "left",
+            Alignment::Center => "center",
+            Alignment::Right => "right",
+            Alignment::Justify => "justify",
+        }
+    }
 }
 
 impl Url {
     pub fn new(description: Option<String>, url: String) -> Self {
-        Self { description, url }
+        Self {
+            description,
+            url,
+            span: Span::default(),
+        }
+    }
+}
+
+impl Figure {
+    pub fn new(content: FigureContent, caption: TextLine) -> Self {
+        Self {
+            content,
+            caption,
+            metadata: None,
+            number: 0,
+            display: None,
+            span: Span::default(),
+        }
+    }
+
+    /// returns the kind of numbering the figure belongs to
+    pub fn get_kind(&self) -> LabelKind {
+        match &self.content {
+            FigureContent::Image(_) => LabelKind::Figure,
+            FigureContent::Table(_) => LabelKind::Table,
+            FigureContent::CodeBlock(_) => LabelKind::Listing,
+        }
+    }
+
+    /// returns the label of the figure or the label of its content
+    pub fn get_label(&self) -> Option<String> {
+        let content_meta = match &self.content {
+            FigureContent::Image(image) => &image.metadata,
+            FigureContent::Table(table) => &table.metadata,
+            FigureContent::CodeBlock(code) => &code.metadata,
+        };
+
+        self.metadata
+            .as_ref()
+            .and_then(|m| m.get_string("label"))
+            .or_else(|| content_meta.as_ref().and_then(|m| m.get_string("label")))
+    }
+
+    /// returns the id that is used to link to the figure
+    pub fn get_anchor(&self) -> String {
+        if let Some(label) = self.get_label() {
+            label
+        } else {
+            format!(
+                "{}-{}",
+                self.get_kind().get_name().to_lowercase(),
+                self.number
+            )
+        }
+    }
+
+    /// returns the number with the configured display e.g. "Figure 1"
+    pub fn get_formatted_number(&self) -> String {
+        self.get_kind()
+            .format_number(&self.display, self.number.to_string().as_str())
+    }
+}
+
+impl Image {
+    /// returns the figure number with the configured display if the image is numbered
+    pub fn get_formatted_number(&self) -> Option<String> {
+        let number = self.number.load(Ordering::Relaxed);
+        if number > 0 {
+            Some(LabelKind::Figure.format_number(&self.display, number.to_string().as_str()))
+        } else {
+            None
+        }
     }
 }
 
@@ -537,13 +1243,10 @@ impl Quote {
     pub fn new(metadata: Option<InlineMetadata>) -> Self {
         Self {
             metadata,
-            text: Vec::new(),
+            content: Vec::new(),
+            span: Span::default(),
         }
     }
-
-    pub fn add_text(&mut self, text: TextLine) {
-        self.text.push(text)
-    }
 }
 
 impl ImportAnchor {
@@ -568,6 +1271,7 @@ impl Placeholder {
             name,
             value: None,
             metadata,
+            span: Span::default(),
         }
     }
 
@@ -576,6 +1280,32 @@ impl Placeholder {
     }
 }
 
+impl InlineMetadata {
+    /// returns the number of decimals numbers are rounded to
+    /// and reports values that aren't a valid number of decimals
+    pub(crate) fn get_decimals(&self, span: &Span, diagnostics: &mut Diagnostics) -> Option<usize> {
+        match self.data.get("decimals") {
+            Some(MetadataValue::Integer(decimals)) if (0..=MAX_DECIMALS).contains(decimals) => {
+                Some(*decimals as usize)
+            }
+            Some(MetadataValue::Integer(decimals)) => {
+                let mut diagnostic = Diagnostic::warning(
+                    D_INVALID_DECIMALS,
+                    format!("invalid number of decimals {}", decimals),
+                    span.clone(),
+                );
+                diagnostic.set_hint(&format!(
+                    "the number of decimals has to be between 0 and {}",
+                    MAX_DECIMALS
+                ));
+                diagnostics.add(diagnostic);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
 pub trait Metadata {
     fn get_bool(&self, key: &str) -> bool;
     fn get_string(&self, key: &str) -> Option<String>;
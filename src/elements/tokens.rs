@@ -32,6 +32,9 @@ pub(crate) const R_BRACE: char = '{';
 pub(crate) const L_BRACE: char = '}';
 pub(crate) const PERCENT: char = '%';
 pub(crate) const COMMA: char = ',';
+pub(crate) const DOLLAR: char = '$';
+pub(crate) const AT: char = '@';
+pub(crate) const SLASH: char = '/';
 
 // aliases
 
@@ -61,6 +64,22 @@ pub(crate) const BIBREF_CLOSE: char = L_BRACKET;
 pub(crate) const BIB_KEY_OPEN: char = R_BRACKET;
 pub(crate) const BIB_KEY_CLOSE: char = L_BRACKET;
 pub(crate) const BIB_DATA_START: char = COLON;
+pub(crate) const CAPTION_START: char = COLON;
+pub(crate) const LABEL_REF_OPEN: char = R_BRACKET;
+pub(crate) const LABEL_REF: char = AT;
+pub(crate) const LABEL_REF_CLOSE: char = L_BRACKET;
+pub(crate) const FOOTNOTE_OPEN: char = R_BRACKET;
+pub(crate) const FOOTNOTE_REF: char = ASTERISK;
+pub(crate) const FOOTNOTE_CLOSE: char = L_BRACKET;
+pub(crate) const FOOTNOTE_DATA_START: char = COLON;
+pub(crate) const GLOSSARY_OPEN: char = R_BRACKET;
+pub(crate) const GLOSSARY_REF: char = TILDE;
+pub(crate) const GLOSSARY_CLOSE: char = L_BRACKET;
+pub(crate) const GLOSSARY_DATA_START: char = COLON;
+pub(crate) const INDEX_OPEN: char = R_BRACKET;
+pub(crate) const INDEX_MARKER: char = HASH;
+pub(crate) const INDEX_CLOSE: char = L_BRACKET;
+pub(crate) const INDEX_SUB_ENTRY: char = BANG;
 pub(crate) const TEMP_VAR_OPEN: char = R_BRACE;
 pub(crate) const TEMP_VAR_CLOSE: char = L_BRACE;
 pub(crate) const TEMPLATE: char = PERCENT;
@@ -72,12 +91,13 @@ pub(crate) const UNDERLINED: char = UNDERSCR;
 pub(crate) const SUPER: char = UP;
 pub(crate) const EMOJI: char = COLON;
 pub(crate) const BOLD: [char; 2] = [ASTERISK, ASTERISK];
+pub(crate) const MATH: char = DOLLAR;
 
 // groups
 
 pub(crate) const QUOTES: [char; 2] = [SINGLE_QUOTE, DOUBLE_QUOTE];
 
-pub(crate) const BLOCK_SPECIAL_CHARS: [&[char]; 9] = [
+pub(crate) const BLOCK_SPECIAL_CHARS: [&[char]; 11] = [
     &[HASH],
     &[HASH, META_OPEN],
     &[MINUS, SPACE],
@@ -87,9 +107,11 @@ pub(crate) const BLOCK_SPECIAL_CHARS: [&[char]; 9] = [
     &[META_OPEN],
     &[IMPORT_START, IMPORT_OPEN],
     &SQ_CENTERED_START,
+    &SQ_MATH_BLOCK,
+    &SQ_CONTAINER,
 ];
 
-pub(crate) const INLINE_SPECIAL_CHARS: [char; 11] = [
+pub(crate) const INLINE_SPECIAL_CHARS: [char; 12] = [
     BACKTICK,
     TILDE,
     UNDERSCR,
@@ -101,18 +123,29 @@ pub(crate) const INLINE_SPECIAL_CHARS: [char; 11] = [
     SUPER,
     EMOJI,
     COLOR_START,
+    MATH,
 ];
 
-pub(crate) const LIST_SPECIAL_CHARS: [char; 14] = [
-    MINUS, PLUS, ASTERISK, O, '1', '2', '3', '4', '5', '6', '7', '8', '9', '0',
-];
+pub(crate) const LIST_SPECIAL_CHARS: [char; 4] = [MINUS, PLUS, ASTERISK, O];
 
 // sequences
 
 pub(crate) const SQ_CODE_BLOCK: [char; 3] = [BACKTICK, BACKTICK, BACKTICK];
+pub(crate) const SQ_MATH_BLOCK: [char; 2] = [MATH, MATH];
+pub(crate) const SQ_CONTAINER: [char; 3] = [COLON, COLON, COLON];
 pub(crate) const SQ_RULER: [char; 5] = [MINUS, SPACE, MINUS, SPACE, MINUS];
 pub(crate) const SQ_PHOLDER_START: [char; 2] = [PHOLDER_OPEN, PHOLDER_OPEN];
 pub(crate) const SQ_PHOLDER_STOP: [char; 2] = [PHOLDER_CLOSE, PHOLDER_CLOSE];
 pub(crate) const SQ_CENTERED_START: [char; 2] = [PIPE, PIPE];
 pub(crate) const SQ_COLOR_START: [char; 2] = [COLOR_START, COLOR_OPEN];
 pub(crate) const SQ_BIBREF_START: [char; 2] = [BIBREF_OPEN, BIBREF_REF];
+pub(crate) const SQ_LABEL_REF_START: [char; 2] = [LABEL_REF_OPEN, LABEL_REF];
+pub(crate) const SQ_FOOTNOTE_START: [char; 2] = [FOOTNOTE_OPEN, FOOTNOTE_REF];
+pub(crate) const SQ_GLOSSARY_START: [char; 2] = [GLOSSARY_OPEN, GLOSSARY_REF];
+pub(crate) const SQ_INDEX_START: [char; 2] = [INDEX_OPEN, INDEX_MARKER];
+pub(crate) const SQ_MERGED_CELL: [char; 2] = [UP, UP];
+pub(crate) const SQ_LINE_COMMENT: [char; 2] = [SLASH, SLASH];
+pub(crate) const SQ_COMMENT_START: [char; 2] = [SLASH, ASTERISK];
+pub(crate) const SQ_COMMENT_END: [char; 2] = [ASTERISK, SLASH];
+pub(crate) const SQ_ANNOTATION_START: [char; 3] = [R_BRACE, GT, GT];
+pub(crate) const SQ_ANNOTATION_END: [char; 3] = [LT, LT, L_BRACE];
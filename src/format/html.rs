@@ -1,11 +1,18 @@
 use crate::elements::*;
+use crate::format::mathml::to_mathml;
 use crate::format::PlaceholderTemplate;
 use crate::references::bibliography::{BibEntry, BibReference};
 use crate::references::configuration::Value;
+use crate::references::footnotes::{Footnote, FootnoteReference};
+use crate::references::glossary::{GlossaryEntry, GlossaryList, GlossaryReference};
+use crate::references::index::{IndexEntry, IndexList, IndexMarker};
+use crate::references::labels::LabelReference;
 use crate::references::templates::{Template, TemplateVariable};
 use htmlescape::{encode_attribute, encode_minimal};
 use minify::html::minify;
 use std::cell::RefCell;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
 use syntect::highlighting::ThemeSet;
 use syntect::html::highlighted_html_for_string;
 use syntect::parsing::SyntaxSet;
@@ -24,6 +31,40 @@ pub trait ToHtml {
     fn to_html(&self) -> String;
 }
 
+/// returns the list of footnotes placed at the end of a section or document
+fn get_footnote_list(footnotes: &[Arc<RwLock<Footnote>>]) -> String {
+    if footnotes.is_empty() {
+        return "".to_string();
+    }
+    let inner = footnotes.iter().fold("".to_string(), |a, b| {
+        format!("{}{}", a, b.read().unwrap().to_html())
+    });
+    format!("<div class='footnotes'><ol>{}</ol></div>", inner)
+}
+
+/// returns if the line defines a footnote or glossary entry that isn't rendered in place
+fn is_definition(line: &Line) -> bool {
+    matches!(line, Line::Footnote(_) | Line::GlossaryEntry(_))
+}
+
+/// returns the style attribute for the text alignment of a table cell
+fn get_alignment_style(alignment: &Option<Alignment>) -> String {
+    if let Some(alignment) = alignment {
+        format!(" style='text-align: {}'", alignment.get_name())
+    } else {
+        String::new()
+    }
+}
+
+/// returns the id attribute for elements with a label in their metadata
+fn get_label_id(metadata: &Option<InlineMetadata>) -> String {
+    if let Some(label) = metadata.as_ref().and_then(|m| m.get_string("label")) {
+        format!(" id='{}'", encode_attribute(label.as_str()))
+    } else {
+        String::new()
+    }
+}
+
 impl ToHtml for Element {
     fn to_html(&self) -> String {
         match self {
@@ -40,8 +81,8 @@ impl ToHtml for Line {
             Line::Text(text) => text.to_html(),
             Line::Ruler(ruler) => ruler.to_html(),
             Line::Anchor(anchor) => anchor.to_html(),
-            Line::Centered(centered) => centered.to_html(),
             Line::BibEntry(bib) => bib.read().unwrap().to_html(),
+            Line::Footnote(_) | Line::GlossaryEntry(_) => "".to_string(),
         }
     }
 }
@@ -64,6 +105,12 @@ impl ToHtml for Inline {
             Inline::Colored(colored) => colored.to_html(),
             Inline::BibReference(bibref) => bibref.read().unwrap().to_html(),
             Inline::TemplateVar(var) => var.read().unwrap().to_html(),
+            Inline::Math(math) => math.to_html(),
+            Inline::Reference(reference) => reference.read().unwrap().to_html(),
+            Inline::Footnote(footnote) => footnote.read().unwrap().to_html(),
+            Inline::GlossaryReference(reference) => reference.read().unwrap().to_html(),
+            Inline::IndexMarker(marker) => marker.read().unwrap().to_html(),
+            Inline::Annotation(annotation) => annotation.read().unwrap().to_html(),
         }
     }
 }
@@ -76,9 +123,19 @@ impl ToHtml for Block {
             Block::Table(table) => table.to_html(),
             Block::CodeBlock(code) => code.to_html(),
             Block::Quote(quote) => quote.to_html(),
+            Block::Admonition(admonition) => admonition.to_html(),
+            Block::Details(details) => details.to_html(),
+            Block::Tabs(tabs) => tabs.to_html(),
+            Block::Columns(columns) => columns.to_html(),
+            Block::Aligned(aligned) => aligned.to_html(),
+            Block::Raw(raw) => raw.to_html(),
             Block::Section(section) => section.to_html(),
             Block::Import(import) => import.to_html(),
             Block::Placeholder(placeholder) => placeholder.read().unwrap().to_html(),
+            Block::Math(math) => math.to_html(),
+            Block::Figure(figure) => figure.to_html(),
+            Block::GlossaryList(glossary) => glossary.to_html(),
+            Block::Index(index) => index.to_html(),
         }
     }
 }
@@ -98,10 +155,11 @@ impl ToHtml for MetadataValue {
 
 impl ToHtml for Document {
     fn to_html(&self) -> String {
-        let inner = self
+        let mut inner = self
             .elements
             .iter()
             .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+        inner.push_str(&get_footnote_list(&self.footnote_list));
         let path = if let Some(path) = &self.path {
             format!("path='{}'", encode_attribute(path.as_str()))
         } else {
@@ -109,9 +167,14 @@ impl ToHtml for Document {
         };
         if self.is_root {
             let style = minify(std::include_str!("assets/style.css"));
+            let class = if self.is_review_mode() {
+                "content review"
+            } else {
+                "content"
+            };
             format!(
-                "<!DOCTYPE html>\n<html><head {}><style>{}</style></head><body><div class='content'>{}</div></body></html>",
-                path, style, inner
+                "<!DOCTYPE html>\n<html><head {}><style>{}</style></head><body><div class='{}'>{}</div></body></html>",
+                path, style, class, inner
             )
         } else {
             format!(
@@ -139,7 +202,13 @@ impl ToHtml for Section {
             .elements
             .iter()
             .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
-        format!("<section>{}{}</section>", self.header.to_html(), inner)
+        format!(
+            "<section{}>{}{}{}</section>",
+            get_label_id(&self.metadata),
+            self.header.to_html(),
+            inner,
+            get_footnote_list(&self.footnotes)
+        )
     }
 }
 
@@ -159,7 +228,12 @@ impl ToHtml for Paragraph {
         let inner = self
             .elements
             .iter()
+            .filter(|l| !is_definition(l))
             .fold("".to_string(), |a, b| combine_with_lb!(a, b));
+        // paragraphs that only contain definitions are rendered where the definitions are listed
+        if inner.is_empty() && self.elements.iter().any(is_definition) {
+            return "".to_string();
+        }
         format!("<div class='paragraph'>{}</div>", inner)
     }
 }
@@ -170,11 +244,7 @@ impl ToHtml for List {
             .items
             .iter()
             .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
-        if self.ordered {
-            format!("<ol>{}</ol>", inner)
-        } else {
-            format!("<ul>{}</ul>", inner)
-        }
+        get_list_html(self.ordered, self.style, self.start, &inner)
     }
 }
 
@@ -184,47 +254,43 @@ impl ToHtml for ListItem {
             .children
             .iter()
             .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+        let content = get_block_content_html(&self.content);
         if let Some(first) = self.children.first() {
-            if first.ordered {
-                format!("<li>{}<ol>{}</ol></li>", self.text.to_html(), inner)
-            } else {
-                format!("<li>{}<ul>{}</ul></li>", self.text.to_html(), inner)
-            }
+            format!(
+                "<li>{}{}</li>",
+                content,
+                get_list_html(first.ordered, first.style, first.number, &inner)
+            )
         } else {
-            format!("<li>{}</li>", self.text.to_html())
+            format!("<li>{}</li>", content)
         }
     }
 }
 
 impl ToHtml for Table {
     fn to_html(&self) -> String {
-        let head = self.header.cells.iter().fold("".to_string(), |a, b| {
-            format!("{}<th>{}</th>", a, b.text.to_html())
-        });
-        let body = self
-            .rows
-            .iter()
-            .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
-        format!(
-            "<div class='tableWrapper'><table><tr>{}<tr>{}</table></div>",
-            head, body
-        )
+        get_table_html(self, "")
     }
 }
 
 impl ToHtml for Row {
     fn to_html(&self) -> String {
-        let inner = self
-            .cells
-            .iter()
-            .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
-        format!("<tr>{}</tr>", inner)
+        get_row_html(self, "td")
     }
 }
 
 impl ToHtml for Cell {
     fn to_html(&self) -> String {
-        format!("<td>{}</td>", self.text.to_html())
+        get_cell_html(self, "td")
+    }
+}
+
+impl ToHtml for MathBlock {
+    fn to_html(&self) -> String {
+        format!(
+            "<div class='math'>{}</div>",
+            to_mathml(self.expression.as_str(), true)
+        )
     }
 }
 
@@ -233,6 +299,7 @@ thread_local! {static TS: RefCell<ThemeSet> = RefCell::new(ThemeSet::load_defaul
 
 impl ToHtml for CodeBlock {
     fn to_html(&self) -> String {
+        let id = get_label_id(&self.metadata);
         if self.language.len() > 0 {
             PS.with(|ps_cell| {
                 let ps = ps_cell.borrow();
@@ -240,7 +307,8 @@ impl ToHtml for CodeBlock {
                     TS.with(|ts_cell| {
                         let ts = ts_cell.borrow();
                         format!(
-                            "<div><code lang='{}'>{}</code></div>",
+                            "<div{}><code lang='{}'>{}</code></div>",
+                            id,
                             encode_attribute(self.language.clone().as_str()),
                             highlighted_html_for_string(
                                 self.code.as_str(),
@@ -252,7 +320,8 @@ impl ToHtml for CodeBlock {
                     })
                 } else {
                     format!(
-                        "<div><code lang='{}'><pre>{}</pre></code></div>",
+                        "<div{}><code lang='{}'><pre>{}</pre></code></div>",
+                        id,
                         encode_attribute(self.language.clone().as_str()),
                         encode_minimal(self.code.as_str())
                     )
@@ -260,30 +329,212 @@ impl ToHtml for CodeBlock {
             })
         } else {
             format!(
-                "<div><code><pre>{}</pre></code></div>",
+                "<div{}><code><pre>{}</pre></code></div>",
+                id,
                 encode_minimal(self.code.as_str())
             )
         }
     }
 }
 
+impl ToHtml for Figure {
+    fn to_html(&self) -> String {
+        let caption = format!(
+            "<figcaption><span class='figureNumber'>{}:</span> {}</figcaption>",
+            encode_minimal(self.get_formatted_number().as_str()),
+            self.caption.to_html()
+        );
+        let has_meta_label = self
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get_string("label"))
+            .is_some();
+        let id = if has_meta_label || self.get_label().is_none() {
+            format!(" id='{}'", encode_attribute(self.get_anchor().as_str()))
+        } else {
+            String::new()
+        };
+        match &self.content {
+            FigureContent::Image(image) => format!(
+                "<figure class='figure'{}><a href='{2}'><img src='{2}' alt='{3}' style='{4}'{5}/></a>{1}</figure>",
+                id,
+                caption,
+                encode_attribute(image.url.url.as_str()),
+                encode_attribute(image.url.description.clone().unwrap_or_default().as_str()),
+                get_image_style(image),
+                get_label_id(&image.metadata)
+            ),
+            FigureContent::Table(table) => format!(
+                "<figure class='figure tableFigure'{}>{}</figure>",
+                id,
+                get_table_html(
+                    table,
+                    &format!(
+                        "<caption><span class='figureNumber'>{}:</span> {}</caption>",
+                        encode_minimal(self.get_formatted_number().as_str()),
+                        self.caption.to_html()
+                    )
+                )
+            ),
+            FigureContent::CodeBlock(code) => format!(
+                "<figure class='figure listingFigure'{}>{}{}</figure>",
+                id,
+                code.to_html(),
+                caption
+            ),
+        }
+    }
+}
+
 impl ToHtml for Quote {
     fn to_html(&self) -> String {
-        let text = self
-            .text
-            .iter()
-            .fold("".to_string(), |a, b| combine_with_lb!(a, b));
-        if let Some(meta) = self.metadata.clone() {
+        let text = get_block_content_html(&self.content);
+        let id = get_label_id(&self.metadata);
+        let meta = if let Some(meta) = &self.metadata {
+            meta.to_html()
+        } else {
+            String::new()
+        };
+        if !meta.is_empty() {
             format!(
-                "<div class='quote'><blockquote>{}</blockquote><span class='metadata'>{}</span></div>",
-                text, meta.to_html()
+                "<div class='quote'{}><blockquote>{}</blockquote><span class='metadata'>{}</span></div>",
+                id, text, meta
             )
         } else {
-            format!("<div class='quote'><blockquote>{}</blockquote></div>", text)
+            format!(
+                "<div class='quote'{}><blockquote>{}</blockquote></div>",
+                id, text
+            )
         }
     }
 }
 
+impl ToHtml for Admonition {
+    fn to_html(&self) -> String {
+        let title = if let Some(title) = &self.title {
+            title.to_html()
+        } else {
+            let mut chars = self.kind.chars();
+            chars
+                .next()
+                .map(|c| c.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        };
+        let content = self
+            .content
+            .iter()
+            .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+
+        format!(
+            "<div class='admonition {}'{}><div class='admonitionTitle'>{}</div><div class='admonitionContent'>{}</div></div>",
+            self.kind,
+            get_label_id(&self.metadata),
+            title,
+            content
+        )
+    }
+}
+
+impl ToHtml for Details {
+    fn to_html(&self) -> String {
+        let summary = if let Some(summary) = &self.summary {
+            summary.to_html()
+        } else {
+            "Details".to_string()
+        };
+        let open = if self.metadata.as_ref().is_some_and(|m| m.get_bool("open")) {
+            " open"
+        } else {
+            ""
+        };
+        let content = self
+            .content
+            .iter()
+            .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+
+        format!(
+            "<details class='details'{}{}><summary>{}</summary>{}</details>",
+            get_label_id(&self.metadata),
+            open,
+            summary,
+            content
+        )
+    }
+}
+
+impl ToHtml for Tabs {
+    fn to_html(&self) -> String {
+        let group = if let Some(label) = self.metadata.as_ref().and_then(|m| m.get_string("label"))
+        {
+            label
+        } else {
+            format!("tabs-{}", self.number.load(Ordering::Relaxed))
+        };
+        let panes = self
+            .panes
+            .iter()
+            .enumerate()
+            .fold("".to_string(), |a, (index, pane)| {
+                let id = encode_attribute(format!("{}-{}", group, index + 1).as_str());
+                let title = if let Some(title) = &pane.title {
+                    title.to_html()
+                } else {
+                    format!("Tab {}", index + 1)
+                };
+                let content = pane
+                    .content
+                    .iter()
+                    .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+                format!(
+                    "{0}<input type='radio' class='tabInput' name='{1}' id='{2}' aria-controls='{2}&#x2D;pane'{3}>\
+                     <label class='tabLabel' for='{2}' id='{2}&#x2D;label'>{4}</label>\
+                     <div class='tabPane' id='{2}&#x2D;pane' role='region' aria-labelledby='{2}&#x2D;label'>{5}</div>",
+                    a,
+                    encode_attribute(group.as_str()),
+                    id,
+                    if index == 0 { " checked" } else { "" },
+                    title,
+                    content
+                )
+            });
+
+        format!(
+            "<div class='tabs' id='{}'>{}</div>",
+            encode_attribute(group.as_str()),
+            panes
+        )
+    }
+}
+
+impl ToHtml for Columns {
+    fn to_html(&self) -> String {
+        let widths: Vec<String> = self
+            .metadata
+            .as_ref()
+            .and_then(|m| m.get_string("widths"))
+            .map(|widths| widths.split_whitespace().map(get_column_width).collect())
+            .unwrap_or_default();
+        let template = if widths.len() == self.columns.len() {
+            widths.join(" ")
+        } else {
+            format!("repeat({}, 1fr)", self.columns.len())
+        };
+        let columns = self.columns.iter().fold("".to_string(), |a, column| {
+            let content = column
+                .iter()
+                .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+            format!("{}<div class='column'>{}</div>", a, content)
+        });
+
+        format!(
+            "<div class='columns'{} style='grid-template-columns: {};'>{}</div>",
+            get_label_id(&self.metadata),
+            template,
+            columns
+        )
+    }
+}
+
 impl ToHtml for Ruler {
     fn to_html(&self) -> String {
         "<hr>".to_string()
@@ -298,37 +549,78 @@ impl ToHtml for TextLine {
     }
 }
 
+/// returns the css width of a column where plain numbers are fractions
+/// of the available space
+fn get_column_width(width: &str) -> String {
+    if width.parse::<f64>().is_ok() {
+        format!("{}fr", width)
+    } else if width
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '%')
+    {
+        width.to_string()
+    } else {
+        "1fr".to_string()
+    }
+}
+
+/// returns the css style of an image for its size metadata
+fn get_image_style(image: &Image) -> String {
+    let mut style = String::new();
+    if let Some(meta) = &image.metadata {
+        if let Some(width) = meta.data.get("width") {
+            style = format!("{}width: {};", style, width.to_html())
+        }
+        if let Some(height) = meta.data.get("height") {
+            style = format!("{}height: {};", style, height.to_html())
+        }
+    }
+
+    style
+}
+
 impl ToHtml for Image {
     fn to_html(&self) -> String {
-        let mut style = String::new();
-        if let Some(meta) = &self.metadata {
-            if let Some(width) = meta.data.get("width") {
-                style = format!("{}width: {};", style, width.to_html())
-            }
-            if let Some(height) = meta.data.get("height") {
-                style = format!("{}height: {};", style, height.to_html())
-            }
-        }
-        if let Some(description) = self.url.description.clone() {
+        let id = get_label_id(&self.metadata);
+        let style = get_image_style(self);
+        let number = self.get_formatted_number();
+        if self.url.description.is_some() || number.is_some() {
+            let description = self.url.description.clone().unwrap_or_default();
+            let label = match &number {
+                Some(number) if description.is_empty() => format!(
+                    "<span class='figureNumber'>{}</span>",
+                    encode_minimal(number.as_str())
+                ),
+                // the space is escaped as minify removes whitespace between tags
+                Some(number) => format!(
+                    "<span class='figureNumber'>{}:</span>&#32;{}",
+                    encode_minimal(number.as_str()),
+                    encode_minimal(description.as_str())
+                ),
+                None => encode_minimal(description.as_str()),
+            };
             minify(
                 format!(
-                    "<div class='figure'>\
+                    "<div class='figure'{4}>\
                      <a href={0}>\
                      <img src='{0}' alt='{1}' style='{2}'/>\
                      </a>\
-                     <label class='imageDescription'>{1}</label>\
+                     <label class='imageDescription'>{3}</label>\
                      </div>",
                     encode_attribute(self.url.url.clone().as_str()),
                     encode_attribute(description.as_str()),
-                    style
+                    style,
+                    label,
+                    id
                 )
                 .as_str(),
             )
         } else {
             format!(
-                "<a href={0}><img src='{0}' style='{1}'/></a>",
+                "<a href={0}{2}><img src='{0}' style='{1}'/></a>",
                 self.url.url.clone(),
-                style
+                style,
+                id
             )
         }
     }
@@ -373,6 +665,12 @@ impl ToHtml for MonospaceText {
     }
 }
 
+impl ToHtml for Math {
+    fn to_html(&self) -> String {
+        to_mathml(self.expression.as_str(), false)
+    }
+}
+
 impl ToHtml for Url {
     fn to_html(&self) -> String {
         if let Some(description) = self.description.clone() {
@@ -427,16 +725,45 @@ impl ToHtml for InlineMetadata {
 
             template.render()
         } else {
-            self.data.iter().fold("".to_string(), |s, (k, v)| {
-                format!("{} {}={},", s, k, v.to_html())
-            })
+            self.data
+                .iter()
+                .filter(|(k, _)| k.as_str() != "label")
+                .fold("".to_string(), |s, (k, v)| {
+                    format!("{} {}={},", s, k, v.to_html())
+                })
+        }
+    }
+}
+
+impl ToHtml for RawBlock {
+    fn to_html(&self) -> String {
+        if self.format == "html" {
+            self.content.clone()
+        } else {
+            String::new()
         }
     }
 }
 
-impl ToHtml for Centered {
+impl ToHtml for Aligned {
     fn to_html(&self) -> String {
-        format!("<div class='centered'>{}</div>", self.line.to_html())
+        let content = self
+            .content
+            .iter()
+            .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+
+        // centered blocks keep the class that `||` lines were rendered with before
+        let class = match self.alignment {
+            Alignment::Center => " centered",
+            _ => "",
+        };
+
+        format!(
+            "<div class='aligned {}{}'>{}</div>",
+            self.alignment.get_name(),
+            class,
+            content
+        )
     }
 }
 
@@ -480,6 +807,201 @@ impl ToHtml for BibReference {
     }
 }
 
+impl ToHtml for LabelReference {
+    fn to_html(&self) -> String {
+        format!(
+            "<a class='labelReference' href='#{}'>{}</a>",
+            encode_attribute(self.key.as_str()),
+            encode_minimal(self.get_formatted().as_str())
+        )
+    }
+}
+
+impl ToHtml for FootnoteReference {
+    fn to_html(&self) -> String {
+        let key = encode_attribute(self.key.as_str());
+        if let Some(footnote) = &self.footnote {
+            format!(
+                "<sup class='footnoteReference' id='fnref-{0}-{1}'><a href='#fn-{0}'>{2}</a></sup>",
+                key,
+                self.index,
+                footnote.read().unwrap().number
+            )
+        } else {
+            format!("<sup class='footnoteReference'>{}?</sup>", key)
+        }
+    }
+}
+
+impl ToHtml for Footnote {
+    fn to_html(&self) -> String {
+        let key = encode_attribute(self.key.as_str());
+        let backlinks = (1..=self.ref_count).fold("".to_string(), |a, i| {
+            format!(
+                "{} <a class='footnoteBackref' href='#fnref-{}-{}'>&#8617;</a>",
+                a, key, i
+            )
+        });
+        format!(
+            "<li id='fn-{}' value='{}'>{}{}</li>",
+            key,
+            self.number,
+            self.text.to_html(),
+            backlinks
+        )
+    }
+}
+
+impl ToHtml for GlossaryReference {
+    fn to_html(&self) -> String {
+        let text = encode_minimal(self.get_formatted().as_str());
+        if let Some(entry) = &self.entry {
+            let entry = entry.read().unwrap();
+            let key = encode_attribute(self.key.as_str());
+            let title = if let Some(long) = &entry.long {
+                format!(" title='{}'", encode_attribute(long.as_str()))
+            } else {
+                "".to_string()
+            };
+            format!(
+                "<a class='glossaryReference' id='glossref-{0}-{1}' href='#glossary-{0}'{2}>{3}</a>",
+                key, self.index, title, text
+            )
+        } else {
+            text
+        }
+    }
+}
+
+impl ToHtml for GlossaryEntry {
+    fn to_html(&self) -> String {
+        let key = encode_attribute(self.key.as_str());
+        let mut description = String::new();
+        if let Some(long) = &self.long {
+            description.push_str(&encode_minimal(long.as_str()));
+        }
+        if let Some(text) = &self.description {
+            if !description.is_empty() {
+                description.push_str(": ");
+            }
+            description.push_str(&encode_minimal(text.as_str()));
+        }
+        let backlinks = (1..=self.ref_count).fold("".to_string(), |a, i| {
+            format!(
+                "{} <a class='glossaryBackref' href='#glossref-{}-{}'>&#8617;</a>",
+                a, key, i
+            )
+        });
+        format!(
+            "<dt id='glossary-{}'>{}</dt><dd>{}{}</dd>",
+            key,
+            encode_minimal(self.term.as_str()),
+            description,
+            backlinks
+        )
+    }
+}
+
+impl ToHtml for GlossaryList {
+    fn to_html(&self) -> String {
+        let inner = self.entries.iter().fold("".to_string(), |a, b| {
+            format!("{}{}", a, b.read().unwrap().to_html())
+        });
+        format!("<dl class='glossary'>{}</dl>", inner)
+    }
+}
+
+impl ToHtml for Annotation {
+    fn to_html(&self) -> String {
+        if !self.visible {
+            return "".to_string();
+        }
+        let mut header = String::new();
+        if let Some(meta) = &self.metadata {
+            if let Some(author) = meta.data.get("author") {
+                header.push_str(&format!(
+                    "<span class='annotationAuthor'>{}</span>",
+                    author.to_html()
+                ));
+            }
+            if let Some(date) = meta.data.get("date") {
+                header.push_str(&format!(
+                    "<span class='annotationDate'>{}</span>",
+                    date.to_html()
+                ));
+            }
+        }
+        if !header.is_empty() {
+            header = format!("<span class='annotationHeader'>{}</span>", header);
+        }
+
+        format!(
+            "<span class='annotation'><span class='annotationNote'>{}{}</span></span>",
+            header,
+            encode_minimal(self.text.as_str())
+        )
+    }
+}
+
+impl ToHtml for IndexMarker {
+    fn to_html(&self) -> String {
+        if self.number == 0 {
+            return "".to_string();
+        }
+        format!(
+            "<span class='indexMarker' id='{}'></span>",
+            self.get_anchor()
+        )
+    }
+}
+
+impl ToHtml for IndexEntry {
+    fn to_html(&self) -> String {
+        let links = self
+            .anchors
+            .iter()
+            .enumerate()
+            .map(|(i, anchor)| format!("<a href='#{}'>{}</a>", anchor, i + 1))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let separator = if links.is_empty() { "" } else { ", " };
+        let children = if self.children.is_empty() {
+            "".to_string()
+        } else {
+            format!(
+                "<ul>{}</ul>",
+                self.children
+                    .iter()
+                    .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()))
+            )
+        };
+        format!(
+            "<li>{}{}{}{}</li>",
+            encode_minimal(self.term.as_str()),
+            separator,
+            links,
+            children
+        )
+    }
+}
+
+impl ToHtml for IndexList {
+    fn to_html(&self) -> String {
+        let inner = self.groups.iter().fold("".to_string(), |a, group| {
+            format!(
+                "{}<div class='indexGroup'><div class='indexLetter'>{}</div><ul>{}</ul></div>",
+                a,
+                encode_minimal(group.letter.to_string().as_str()),
+                group
+                    .entries
+                    .iter()
+                    .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()))
+            )
+        });
+        format!("<div class='index'>{}</div>", inner)
+    }
+}
+
 impl ToHtml for BibEntry {
     fn to_html(&self) -> String {
         if !self.is_visible() {
@@ -494,9 +1016,7 @@ impl ToHtml for BibEntry {
                     .map(|(k, v)| {
                         (
                             k.clone(),
-                            Element::Inline(Box::new(Inline::Plain(PlainText {
-                                value: v.clone(),
-                            }))),
+                            Element::Inline(Box::new(Inline::Plain(PlainText::new(v.clone())))),
                         )
                     })
                     .collect();
@@ -551,3 +1071,122 @@ impl ToHtml for TemplateVariable {
         }
     }
 }
+
+/// returns the html of a list with the start and type attributes of ordered lists
+fn get_list_html(ordered: bool, style: ListStyle, start: usize, inner: &str) -> String {
+    if !ordered {
+        return format!("<ul>{}</ul>", inner);
+    }
+    let mut attributes = String::new();
+    if start != 1 {
+        attributes.push_str(&format!(" start='{}'", start));
+    }
+    let list_type = match style {
+        ListStyle::Decimal => None,
+        ListStyle::LowerAlpha => Some("a"),
+        ListStyle::UpperAlpha => Some("A"),
+        ListStyle::LowerRoman => Some("i"),
+        ListStyle::UpperRoman => Some("I"),
+    };
+    if let Some(list_type) = list_type {
+        attributes.push_str(&format!(" type='{}'", list_type));
+    }
+
+    format!("<ol{}>{}</ol>", attributes, inner)
+}
+
+/// returns the html of the blocks inside a list item or quote
+/// The lines of a leading paragraph are rendered without a paragraph element
+fn get_block_content_html(content: &[Block]) -> String {
+    let (first, blocks) = match content {
+        [Block::Paragraph(paragraph), blocks @ ..] => (
+            paragraph
+                .elements
+                .iter()
+                .fold("".to_string(), |a, b| combine_with_lb!(a, b)),
+            blocks,
+        ),
+        blocks => (String::new(), blocks),
+    };
+
+    blocks
+        .iter()
+        .fold(first, |a, b| format!("{}{}", a, b.to_html()))
+}
+
+/// returns the html of a table with an optional caption element
+/// The class and style of the table are taken from its metadata
+fn get_table_html(table: &Table, caption: &str) -> String {
+    let mut classes = Vec::new();
+    let mut style = String::new();
+    if let Some(meta) = &table.metadata {
+        if let Some(class) = meta.get_string("class") {
+            classes.push(class);
+        }
+        if let Some(MetadataValue::Bool(false)) = meta.data.get("striped") {
+            classes.push("unstriped".to_string());
+        }
+        if meta.get_bool("compact") {
+            classes.push("compact".to_string());
+        }
+        if let Some(width) = meta.data.get("width") {
+            style = format!(" style='width: {};'", width.to_html());
+        }
+    }
+    let class = if classes.is_empty() {
+        String::new()
+    } else {
+        format!(" class='{}'", encode_attribute(classes.join(" ").as_str()))
+    };
+    let head = table.header.iter().fold("".to_string(), |a, b| {
+        format!("{}{}", a, get_row_html(b, "th"))
+    });
+    let body = table
+        .rows
+        .iter()
+        .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html()));
+    let head = if head.is_empty() {
+        head
+    } else {
+        format!("<thead>{}</thead>", head)
+    };
+    format!(
+        "<div class='tableWrapper'{}><table{}{}>{}{}<tbody>{}</tbody></table></div>",
+        get_label_id(&table.metadata),
+        class,
+        style,
+        caption,
+        head,
+        body
+    )
+}
+
+/// returns the html of a table row with the given tag for its cells
+fn get_row_html(row: &Row, tag: &str) -> String {
+    let inner = row.cells.iter().fold("".to_string(), |a, b| {
+        format!("{}{}", a, get_cell_html(b, tag))
+    });
+    format!("<tr>{}</tr>", inner)
+}
+
+/// returns the html of a table cell with the given tag
+/// A cell with a single paragraph is rendered without the paragraph wrapper
+fn get_cell_html(cell: &Cell, tag: &str) -> String {
+    let mut attributes = get_alignment_style(&cell.alignment);
+    if cell.colspan > 1 {
+        attributes += &format!(" colspan='{}'", cell.colspan);
+    }
+    if cell.rowspan > 1 {
+        attributes += &format!(" rowspan='{}'", cell.rowspan);
+    }
+    let inner = match cell.content.as_slice() {
+        [Block::Paragraph(paragraph)] => paragraph
+            .elements
+            .iter()
+            .fold("".to_string(), |a, b| combine_with_lb!(a, b)),
+        blocks => blocks
+            .iter()
+            .fold("".to_string(), |a, b| format!("{}{}", a, b.to_html())),
+    };
+    format!("<{0}{1}>{2}</{0}>", tag, attributes, inner)
+}
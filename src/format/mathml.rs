@@ -0,0 +1,505 @@
+use htmlescape::encode_minimal;
+
+#[derive(Clone, Debug, PartialEq)]
+enum MathToken {
+    Command(String),
+    Number(String),
+    Letter(char),
+    Operator(char),
+    Text(String),
+    GroupOpen,
+    GroupClose,
+    Superscript,
+    Subscript,
+    Separator,
+}
+
+struct MathParser {
+    tokens: Vec<MathToken>,
+    index: usize,
+    display: bool,
+}
+
+/// Converts a LaTeX like math expression into MathML
+pub fn to_mathml(expression: &str, display: bool) -> String {
+    let mut parser = MathParser {
+        tokens: tokenize(expression),
+        index: 0,
+        display,
+    };
+    let mut inner = String::new();
+    while parser.index < parser.tokens.len() {
+        inner.push_str(parser.parse_sequence().as_str());
+        // skips unbalanced closing tokens that ended the sequence
+        parser.index += 1;
+    }
+
+    format!(
+        "<math xmlns='http://www.w3.org/1998/Math/MathML' display='{}'><mrow>{}</mrow></math>",
+        if display { "block" } else { "inline" },
+        inner
+    )
+}
+
+fn tokenize(expression: &str) -> Vec<MathToken> {
+    let chars: Vec<char> = expression.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        let ch = chars[index];
+        index += 1;
+        match ch {
+            '\\' => {
+                let mut name = String::new();
+                while index < chars.len() && chars[index].is_ascii_alphabetic() {
+                    name.push(chars[index]);
+                    index += 1;
+                }
+                if name.is_empty() && index < chars.len() {
+                    name.push(chars[index]);
+                    index += 1;
+                }
+                let is_text =
+                    ["text", "mathrm", "operatorname", "mathbf", "mathbb"].contains(&name.as_str());
+                tokens.push(MathToken::Command(name));
+
+                if is_text && chars.get(index) == Some(&'{') {
+                    // text keeps its whitespace so it is read as a whole
+                    let mut text = String::new();
+                    let mut depth = 0;
+                    index += 1;
+                    while index < chars.len() {
+                        match chars[index] {
+                            '{' => depth += 1,
+                            '}' if depth == 0 => break,
+                            '}' => depth -= 1,
+                            _ => {}
+                        }
+                        text.push(chars[index]);
+                        index += 1;
+                    }
+                    index += 1;
+                    tokens.push(MathToken::Text(text));
+                }
+            }
+            '{' => tokens.push(MathToken::GroupOpen),
+            '}' => tokens.push(MathToken::GroupClose),
+            '^' => tokens.push(MathToken::Superscript),
+            '_' => tokens.push(MathToken::Subscript),
+            '&' => tokens.push(MathToken::Separator),
+            _ if ch.is_whitespace() => {}
+            _ if ch.is_ascii_digit() => {
+                let mut number = ch.to_string();
+                while index < chars.len()
+                    && (chars[index].is_ascii_digit()
+                        || (chars[index] == '.'
+                            && chars.get(index + 1).is_some_and(|c| c.is_ascii_digit())))
+                {
+                    number.push(chars[index]);
+                    index += 1;
+                }
+                tokens.push(MathToken::Number(number));
+            }
+            _ if ch.is_alphabetic() => tokens.push(MathToken::Letter(ch)),
+            _ => tokens.push(MathToken::Operator(ch)),
+        }
+    }
+
+    tokens
+}
+
+impl MathParser {
+    fn peek(&self) -> Option<&MathToken> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<MathToken> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    /// parses terms until the end of a group, a table cell or row
+    fn parse_sequence(&mut self) -> String {
+        let mut output = String::new();
+        while let Some(token) = self.peek() {
+            match token {
+                MathToken::GroupClose | MathToken::Separator => break,
+                MathToken::Command(name) if name == "\\" || name == "end" || name == "right" => {
+                    break
+                }
+                _ => output.push_str(self.parse_term().as_str()),
+            }
+        }
+
+        output
+    }
+
+    /// parses an atom with its optional sub- and superscripts
+    fn parse_term(&mut self) -> String {
+        let is_big_operator = if let Some(MathToken::Command(name)) = self.peek() {
+            ["sum", "prod", "coprod", "lim", "bigcup", "bigcap"].contains(&name.as_str())
+        } else {
+            false
+        };
+        let base = self.parse_atom();
+        let mut subscript = None;
+        let mut superscript = None;
+        loop {
+            match self.peek() {
+                Some(MathToken::Subscript) if subscript.is_none() => {
+                    self.index += 1;
+                    subscript = Some(self.parse_atom());
+                }
+                Some(MathToken::Superscript) if superscript.is_none() => {
+                    self.index += 1;
+                    superscript = Some(self.parse_atom());
+                }
+                _ => break,
+            }
+        }
+        let (under, over, both) = if is_big_operator && self.display {
+            ("munder", "mover", "munderover")
+        } else {
+            ("msub", "msup", "msubsup")
+        };
+
+        match (subscript, superscript) {
+            (Some(sub), Some(sup)) => format!("<{0}>{1}{2}{3}</{0}>", both, base, sub, sup),
+            (Some(sub), None) => format!("<{0}>{1}{2}</{0}>", under, base, sub),
+            (None, Some(sup)) => format!("<{0}>{1}{2}</{0}>", over, base, sup),
+            (None, None) => base,
+        }
+    }
+
+    /// parses a single element, a group is returned as one mrow element
+    fn parse_atom(&mut self) -> String {
+        match self.next() {
+            Some(MathToken::GroupOpen) => self.parse_group_rest(),
+            Some(MathToken::Number(number)) => format!("<mn>{}</mn>", number),
+            Some(MathToken::Letter(letter)) => format!("<mi>{}</mi>", letter),
+            Some(MathToken::Operator(operator)) => {
+                format!("<mo>{}</mo>", encode_minimal(operator.to_string().as_str()))
+            }
+            Some(MathToken::Command(name)) => self.parse_command(name.as_str()),
+            Some(MathToken::Text(text)) => format!("<mtext>{}</mtext>", encode_minimal(&text)),
+            Some(MathToken::Superscript) | Some(MathToken::Subscript) => {
+                "<mrow></mrow>".to_string()
+            }
+            _ => {
+                self.index -= 1;
+                "<mrow></mrow>".to_string()
+            }
+        }
+    }
+
+    /// parses the content of a group after the opening brace
+    fn parse_group_rest(&mut self) -> String {
+        let inner = self.parse_sequence();
+        if self.peek() == Some(&MathToken::GroupClose) {
+            self.index += 1;
+        }
+
+        format!("<mrow>{}</mrow>", inner)
+    }
+
+    /// returns the raw text of a group e.g. for \text{}
+    fn parse_raw_group(&mut self) -> String {
+        let mut text = String::new();
+        if let Some(MathToken::Text(raw)) = self.peek() {
+            text = raw.clone();
+            self.index += 1;
+            return text;
+        }
+        if self.peek() != Some(&MathToken::GroupOpen) {
+            return text;
+        }
+        self.index += 1;
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                MathToken::GroupOpen => depth += 1,
+                MathToken::GroupClose if depth == 0 => break,
+                MathToken::GroupClose => depth -= 1,
+                MathToken::Letter(letter) => text.push(letter),
+                MathToken::Operator(operator) => text.push(operator),
+                MathToken::Number(number) => text.push_str(number.as_str()),
+                MathToken::Command(name) => text.push_str(name.as_str()),
+                _ => {}
+            }
+        }
+
+        text
+    }
+
+    fn parse_command(&mut self, name: &str) -> String {
+        if let Some(symbol) = get_identifier(name) {
+            return format!("<mi>{}</mi>", symbol);
+        }
+        if let Some(symbol) = get_operator(name) {
+            return format!("<mo>{}</mo>", symbol);
+        }
+        match name {
+            "frac" | "dfrac" | "tfrac" => {
+                let numerator = self.parse_atom();
+                let denominator = self.parse_atom();
+                format!("<mfrac>{}{}</mfrac>", numerator, denominator)
+            }
+            "sqrt" => {
+                if self.peek() == Some(&MathToken::Operator('[')) {
+                    self.index += 1;
+                    let mut index = String::new();
+                    while let Some(token) = self.peek() {
+                        if *token == MathToken::Operator(']') {
+                            self.index += 1;
+                            break;
+                        }
+                        let start = self.index;
+                        index.push_str(self.parse_term().as_str());
+                        if self.index == start {
+                            // group ends and separators can't be part of the index
+                            break;
+                        }
+                    }
+                    let radicand = self.parse_atom();
+                    format!("<mroot>{}<mrow>{}</mrow></mroot>", radicand, index)
+                } else {
+                    format!("<msqrt>{}</msqrt>", self.parse_atom())
+                }
+            }
+            "text" | "mathrm" | "operatorname" => {
+                format!("<mtext>{}</mtext>", encode_minimal(&self.parse_raw_group()))
+            }
+            "mathbf" => format!(
+                "<mi mathvariant='bold'>{}</mi>",
+                encode_minimal(&self.parse_raw_group())
+            ),
+            "mathbb" => format!(
+                "<mi mathvariant='double-struck'>{}</mi>",
+                encode_minimal(&self.parse_raw_group())
+            ),
+            "sin" | "cos" | "tan" | "cot" | "sec" | "csc" | "arcsin" | "arccos" | "arctan"
+            | "sinh" | "cosh" | "tanh" | "log" | "ln" | "lg" | "exp" | "max" | "min" | "sup"
+            | "inf" | "lim" | "det" | "gcd" | "deg" | "dim" | "arg" | "ker" => {
+                format!("<mi>{}</mi>", name)
+            }
+            "left" => {
+                let open = self.parse_fence();
+                let inner = self.parse_sequence();
+                let close = if let Some(MathToken::Command(name)) = self.peek() {
+                    if name == "right" {
+                        self.index += 1;
+                        self.parse_fence()
+                    } else {
+                        String::new()
+                    }
+                } else {
+                    String::new()
+                };
+                format!("<mrow>{}{}{}</mrow>", open, inner, close)
+            }
+            "begin" => self.parse_environment(),
+            "," => "<mspace width='0.17em'/>".to_string(),
+            ":" | ">" => "<mspace width='0.22em'/>".to_string(),
+            ";" => "<mspace width='0.28em'/>".to_string(),
+            "quad" => "<mspace width='1em'/>".to_string(),
+            "qquad" => "<mspace width='2em'/>".to_string(),
+            "{" | "}" | "|" | "%" | "$" | "#" | "_" => {
+                format!("<mo>{}</mo>", encode_minimal(name))
+            }
+            _ => format!("<merror><mtext>\\{}</mtext></merror>", encode_minimal(name)),
+        }
+    }
+
+    /// parses the delimiter after \left or \right
+    fn parse_fence(&mut self) -> String {
+        let fence = match self.next() {
+            Some(MathToken::Operator('.')) => return String::new(),
+            Some(MathToken::Operator(operator)) => operator.to_string(),
+            Some(MathToken::Command(name)) => match name.as_str() {
+                "{" | "lbrace" => "{".to_string(),
+                "}" | "rbrace" => "}".to_string(),
+                "|" => "‖".to_string(),
+                "langle" => "⟨".to_string(),
+                "rangle" => "⟩".to_string(),
+                _ => String::new(),
+            },
+            _ => {
+                self.index -= 1;
+                String::new()
+            }
+        };
+
+        format!(
+            "<mo stretchy='true' fence='true'>{}</mo>",
+            encode_minimal(fence.as_str())
+        )
+    }
+
+    /// parses a matrix like environment
+    fn parse_environment(&mut self) -> String {
+        let name = self.parse_raw_group();
+        let mut rows = Vec::new();
+        let mut cells = Vec::new();
+        loop {
+            cells.push(format!("<mtd>{}</mtd>", self.parse_sequence()));
+            match self.next() {
+                Some(MathToken::Separator) => {}
+                Some(MathToken::Command(command)) if command == "\\" => {
+                    rows.push(format!("<mtr>{}</mtr>", cells.join("")));
+                    cells.clear();
+                }
+                Some(MathToken::Command(command)) if command == "end" => {
+                    self.parse_raw_group();
+                    break;
+                }
+                None => break,
+                _ => {}
+            }
+        }
+        if cells.len() > 1 || cells.first().is_some_and(|c| c != "<mtd></mtd>") {
+            rows.push(format!("<mtr>{}</mtr>", cells.join("")));
+        }
+        let table = format!("<mtable>{}</mtable>", rows.join(""));
+        let (open, close) = match name.as_str() {
+            "pmatrix" => ("(", ")"),
+            "bmatrix" => ("[", "]"),
+            "Bmatrix" => ("{", "}"),
+            "vmatrix" => ("|", "|"),
+            "Vmatrix" => ("‖", "‖"),
+            "cases" => ("{", ""),
+            _ => return table,
+        };
+
+        format!(
+            "<mrow><mo stretchy='true' fence='true'>{}</mo>{}<mo stretchy='true' fence='true'>{}</mo></mrow>",
+            encode_minimal(open),
+            table,
+            close
+        )
+    }
+}
+
+/// returns the symbol for greek letters and other identifiers
+fn get_identifier(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "infty" => "∞",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "emptyset" => "∅",
+        "hbar" => "ℏ",
+        "ell" => "ℓ",
+        _ => return None,
+    };
+
+    Some(symbol)
+}
+
+/// returns the symbol for operators, relations and arrows
+fn get_operator(name: &str) -> Option<&'static str> {
+    let symbol = match name {
+        "sum" => "∑",
+        "prod" => "∏",
+        "coprod" => "∐",
+        "int" => "∫",
+        "iint" => "∬",
+        "iiint" => "∭",
+        "oint" => "∮",
+        "bigcup" => "⋃",
+        "bigcap" => "⋂",
+        "cdot" => "⋅",
+        "times" => "×",
+        "div" => "÷",
+        "pm" => "±",
+        "mp" => "∓",
+        "circ" => "∘",
+        "ast" => "∗",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "equiv" => "≡",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "propto" => "∝",
+        "ll" => "≪",
+        "gg" => "≫",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "land" | "wedge" => "∧",
+        "lor" | "vee" => "∨",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" | "implies" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "cdots" => "⋯",
+        "ldots" | "dots" => "…",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "mid" => "∣",
+        "parallel" => "∥",
+        "perp" => "⊥",
+        "angle" => "∠",
+        "prime" => "′",
+        _ => return None,
+    };
+
+    Some(symbol)
+}
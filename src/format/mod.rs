@@ -2,6 +2,7 @@ use regex::Regex;
 use std::collections::HashMap;
 
 pub mod html;
+pub mod mathml;
 
 pub struct PlaceholderTemplate {
     value: String,
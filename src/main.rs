@@ -1,5 +1,6 @@
 use colored::Colorize;
 use notify::{watcher, RecursiveMode, Watcher};
+use snekdown::elements::Document;
 use snekdown::format::html::ToHtml;
 use snekdown::Parser;
 use std::fs::write;
@@ -22,6 +23,10 @@ struct Opt {
     #[structopt(short, long, default_value = "html")]
     format: String,
 
+    /// render review annotations as margin notes
+    #[structopt(short, long)]
+    review: bool,
+
     #[structopt(subcommand)]
     sub_command: Option<SubCommand>,
 }
@@ -47,12 +52,15 @@ fn main() {
             )
             .red()
         );
-        return;
+        std::process::exit(1);
     }
 
     match &opt.sub_command {
         Some(SubCommand::Render) | None => {
-            let _ = render(&opt);
+            let (_, success) = render(&opt);
+            if !success {
+                std::process::exit(1);
+            }
         }
         Some(SubCommand::Watch) => watch(&opt),
     };
@@ -60,7 +68,7 @@ fn main() {
 
 /// Watches a file with all of its imports and renders on change
 fn watch(opt: &Opt) {
-    let parser = render(opt);
+    let (parser, _) = render(opt);
     let (tx, rx) = channel();
     let mut watcher = watcher(tx, Duration::from_millis(250)).unwrap();
     for path in parser.get_paths() {
@@ -68,18 +76,23 @@ fn watch(opt: &Opt) {
     }
     while let Ok(_) = rx.recv() {
         println!("---");
-        let parser = render(opt);
+        let (parser, _) = render(opt);
         for path in parser.get_paths() {
             watcher.watch(path, RecursiveMode::NonRecursive).unwrap();
         }
     }
 }
 
-/// Renders the document to the output path
-fn render(opt: &Opt) -> Parser {
+/// Renders the document to the output path and returns
+/// if the document was parsed without errors
+fn render(opt: &Opt) -> (Parser, bool) {
     let start = Instant::now();
     let mut parser = Parser::new_from_file(opt.input.clone()).unwrap();
+    if opt.review {
+        parser.set_review_mode(true);
+    }
     let document = parser.parse();
+    print_diagnostics(&document);
     println!(
         "{}",
         format!("Parsing took:     {:?}", start.elapsed()).italic()
@@ -98,5 +111,14 @@ fn render(opt: &Opt) -> Parser {
         format!("Total:            {:?}", start.elapsed()).italic()
     );
 
-    parser
+    let success = !document.diagnostics.has_errors();
+
+    (parser, success)
+}
+
+/// Prints the diagnostics of the document with their source snippets
+fn print_diagnostics(document: &Document) {
+    for diagnostic in document.diagnostics.iter() {
+        println!("{}\n", diagnostic.format(true));
+    }
 }
@@ -1,22 +1,60 @@
 use crate::elements::tokens::*;
-use crate::elements::{Block, CodeBlock, Import, List, ListItem, Paragraph, Quote, Section, Table};
+use crate::elements::{
+    Admonition, Aligned, Alignment, Block, Cell, CodeBlock, Columns, Details, Figure,
+    FigureContent, Import, Inline, InlineMetadata, Line, List, ListItem, MathBlock, Metadata,
+    MetadataValue, Paragraph, PlainText, Quote, RawBlock, Row, Section, Span, TabPane, Table, Tabs,
+    TextLine,
+};
 use crate::parser::charstate::CharStateMachine;
 use crate::parser::inline::ParseInline;
 use crate::parser::line::ParseLine;
+use crate::utils::diagnostics::{
+    Diagnostic, D_UNCLOSED_COMMENT, D_UNCLOSED_CONTAINER, D_UNKNOWN_COLUMN,
+};
 use crate::utils::parsing::{ParseError, ParseResult};
 use crate::Parser;
+use std::path::Path;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
 pub(crate) trait ParseBlock {
     fn parse_block(&mut self) -> ParseResult<Block>;
+    fn skip_comments(&mut self);
     fn parse_section(&mut self) -> ParseResult<Section>;
     fn parse_code_block(&mut self) -> ParseResult<CodeBlock>;
+    fn parse_math_block(&mut self) -> ParseResult<MathBlock>;
+    fn parse_figure(&mut self, content: FigureContent, start_index: usize) -> Block;
+    fn parse_image_figure(&mut self) -> ParseResult<Figure>;
+    fn parse_caption(&mut self) -> ParseResult<(Option<InlineMetadata>, TextLine)>;
+    fn check_image_figure(&mut self) -> bool;
     fn parse_quote(&mut self) -> ParseResult<Quote>;
+    fn parse_container(&mut self) -> ParseResult<Block>;
+    fn parse_centered(&mut self) -> ParseResult<Aligned>;
+    fn parse_panes(
+        &mut self,
+        container: &Container,
+        pane_name: &str,
+        start_index: usize,
+    ) -> ParseResult<Vec<(Container, Vec<Block>)>>;
+    fn read_container_header(&mut self) -> ParseResult<Container>;
+    fn read_container_content(&mut self, container: &Container) -> Option<(String, Vec<usize>)>;
     fn parse_paragraph(&mut self) -> ParseResult<Paragraph>;
     fn parse_list(&mut self) -> ParseResult<List>;
     fn parse_table(&mut self) -> ParseResult<Table>;
+    fn check_table_separator(&mut self) -> bool;
+    fn parse_table_separator(&mut self) -> ParseResult<Vec<Option<Alignment>>>;
+    fn parse_data_import(&mut self) -> ParseResult<Table>;
     fn parse_import(&mut self) -> ParseResult<Import>;
 }
 
+/// the header of a block that is wrapped in a `:::name` and a `:::` line
+pub(crate) struct Container {
+    name: String,
+    metadata: Option<InlineMetadata>,
+    title: Option<TextLine>,
+    span: Span,
+}
+
 impl ParseBlock for Parser {
     /// Parses a block Token
     fn parse_block(&mut self) -> ParseResult<Block> {
@@ -30,6 +68,8 @@ impl ParseBlock for Parser {
                 self.section_return = None;
             }
         }
+        self.skip_comments();
+        let start_index = self.index;
         let token = if let Ok(section) = self.parse_section() {
             Block::Section(section)
         } else if let Some(_) = self.section_return {
@@ -37,17 +77,35 @@ impl ParseBlock for Parser {
         } else if let Ok(list) = self.parse_list() {
             Block::List(list)
         } else if let Ok(table) = self.parse_table() {
-            Block::Table(table)
+            self.parse_figure(FigureContent::Table(table), start_index)
         } else if let Ok(code_block) = self.parse_code_block() {
-            Block::CodeBlock(code_block)
+            if let Some(format) = get_raw_format(&code_block.language) {
+                Block::Raw(RawBlock {
+                    format,
+                    content: code_block.code,
+                    span: code_block.span,
+                })
+            } else {
+                self.parse_figure(FigureContent::CodeBlock(code_block), start_index)
+            }
+        } else if let Ok(math) = self.parse_math_block() {
+            Block::Math(math)
+        } else if let Ok(block) = self.parse_container() {
+            block
+        } else if let Ok(centered) = self.parse_centered() {
+            Block::Aligned(centered)
         } else if let Ok(quote) = self.parse_quote() {
             Block::Quote(quote)
+        } else if let Ok(table) = self.parse_data_import() {
+            self.parse_figure(FigureContent::Table(table), start_index)
         } else if let Ok(import) = self.parse_import() {
             Block::Import(import)
         } else if let Some(_) = self.section_return {
             return Err(ParseError::new(self.index));
         } else if let Ok(pholder) = self.parse_placeholder() {
             Block::Placeholder(pholder)
+        } else if let Ok(figure) = self.parse_image_figure() {
+            Block::Figure(figure)
         } else if let Ok(paragraph) = self.parse_paragraph() {
             Block::Paragraph(paragraph)
         } else {
@@ -57,6 +115,41 @@ impl ParseBlock for Parser {
         Ok(token)
     }
 
+    /// Skips lines starting with a `//` comment and `/* */` comments
+    /// at the start of a line that can span multiple lines
+    /// A `/*` without a closing `*/` is reported and kept as text
+    fn skip_comments(&mut self) {
+        loop {
+            let start_index = self.index;
+            self.seek_whitespace();
+            if self.check_special_sequence(&SQ_LINE_COMMENT) {
+                self.seek_until_linebreak();
+            } else if self.check_comment_start() {
+                let comment_start = self.index;
+                self.skip_char();
+                self.skip_char();
+                while !self.check_comment_end() {
+                    if self.next_char().is_none() {
+                        let mut diagnostic = Diagnostic::warning(
+                            D_UNCLOSED_COMMENT,
+                            "unclosed comment".to_string(),
+                            self.get_span_between(comment_start, comment_start + 2),
+                        );
+                        diagnostic.set_hint("comments are closed with `*/`");
+                        self.document.diagnostics.add(diagnostic);
+                        let _ = self.revert_to(comment_start);
+                        return;
+                    }
+                }
+                self.skip_char();
+                self.skip_char();
+            } else {
+                let _ = self.revert_to(start_index);
+                return;
+            }
+        }
+    }
+
     /// Parses a section that consists of a header and one or more blocks
     fn parse_section(&mut self) -> ParseResult<Section> {
         let start_index = self.index;
@@ -91,6 +184,7 @@ impl ParseBlock for Parser {
             while let Ok(block) = self.parse_block() {
                 section.add_element(block);
             }
+            section.span = self.get_span(start_index);
 
             self.sections.pop();
             if let Some(sec) = self.sections.last() {
@@ -107,9 +201,12 @@ impl ParseBlock for Parser {
     /// parses a code block
     fn parse_code_block(&mut self) -> ParseResult<CodeBlock> {
         self.seek_whitespace();
+        let start_index = self.index;
         self.assert_special_sequence(&SQ_CODE_BLOCK, self.index)?;
         self.skip_char();
-        let language = self.get_string_until(&[LB], &[])?;
+        let language = self.get_string_until(&[LB, META_OPEN], &[])?;
+        let metadata = self.parse_inline_metadata().ok();
+        self.seek_inline_whitespace();
         self.skip_char();
         let text = self.get_string_until_sequence(&[&SQ_CODE_BLOCK], &[])?;
         for _ in 0..2 {
@@ -117,11 +214,132 @@ impl ParseBlock for Parser {
         }
 
         Ok(CodeBlock {
-            language,
+            language: language.trim().to_string(),
             code: text,
+            metadata,
+            span: self.get_span(start_index),
+        })
+    }
+
+    /// parses a block of display math surrounded by two dollar signs
+    fn parse_math_block(&mut self) -> ParseResult<MathBlock> {
+        let start_index = self.index;
+        self.seek_whitespace();
+        let span_start = self.index;
+        self.assert_special_sequence(&SQ_MATH_BLOCK, start_index)?;
+        self.skip_char();
+        let expression = self.get_string_until_sequence(&[&SQ_MATH_BLOCK], &[])?;
+        if self.index >= self.text.len() {
+            // the text ended without a closing sequence
+            return Err(self.revert_with_error(start_index));
+        }
+        for _ in 0..2 {
+            self.skip_char();
+        }
+        let expression = expression.trim().to_string();
+        if expression.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+
+        Ok(MathBlock {
+            expression,
+            span: self.get_span(span_start),
         })
     }
 
+    /// parses the caption following a table, code block or image line
+    /// and returns a figure. If there is no caption the content is returned as it is
+    fn parse_figure(&mut self, content: FigureContent, start_index: usize) -> Block {
+        let caption = self
+            .parse_caption()
+            .ok()
+            .or_else(|| get_metadata_caption(&content).map(|caption| (None, caption)));
+        if let Some((metadata, caption)) = caption {
+            let mut figure = Figure::new(content, caption);
+            figure.metadata = metadata;
+            figure.display = self
+                .document
+                .config
+                .get_ref_entry(figure.get_kind().get_config_key());
+            figure.span = self.get_span(start_index);
+
+            Block::Figure(figure)
+        } else {
+            match content {
+                FigureContent::Table(table) => Block::Table(table),
+                FigureContent::CodeBlock(code) => Block::CodeBlock(code),
+                FigureContent::Image(image) => {
+                    let mut line = TextLine::new();
+                    line.span = image.span.clone();
+                    line.add_subtext(Inline::Image(image));
+                    let mut paragraph = Paragraph::new();
+                    paragraph.span = line.span.clone();
+                    paragraph.add_element(Line::Text(line));
+                    Block::Paragraph(paragraph)
+                }
+            }
+        }
+    }
+
+    /// parses an image that is alone on its line and followed by a caption
+    fn parse_image_figure(&mut self) -> ParseResult<Figure> {
+        let start_index = self.index;
+        self.seek_whitespace();
+        let image = self.parse_image()?;
+        self.seek_inline_whitespace();
+        if !self.check_linebreak() {
+            return Err(self.revert_with_error(start_index));
+        }
+        if let Block::Figure(figure) = self.parse_figure(FigureContent::Image(image), start_index) {
+            Ok(figure)
+        } else {
+            Err(self.revert_with_error(start_index))
+        }
+    }
+
+    /// parses a caption line that starts with a colon and optional metadata
+    /// e.g. `:[label=fig:arch] The architecture`
+    fn parse_caption(&mut self) -> ParseResult<(Option<InlineMetadata>, TextLine)> {
+        let start_index = self.index;
+        self.seek_inline_whitespace();
+        if self.check_linebreak() {
+            self.skip_char();
+        }
+        self.seek_inline_whitespace();
+        self.assert_special(&CAPTION_START, start_index)?;
+        self.skip_char();
+        let metadata = self.parse_inline_metadata().ok();
+        if metadata.is_none() && !self.check_special(&SPACE) {
+            return Err(self.revert_with_error(start_index));
+        }
+        self.seek_inline_whitespace();
+        let caption = self.parse_text_line()?;
+        if caption.subtext.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+
+        Ok((metadata, caption))
+    }
+
+    /// checks if the current line contains an image and the next line a caption
+    /// without parsing them
+    fn check_image_figure(&mut self) -> bool {
+        let start_index = self.index;
+        let mut is_figure = false;
+        self.seek_inline_whitespace();
+        if self.check_special(&IMG_START) {
+            self.seek_until_linebreak();
+            self.seek_inline_whitespace();
+            if self.check_special(&CAPTION_START) {
+                self.skip_char();
+                is_figure = self.check_special(&SPACE) || self.check_special(&META_OPEN);
+            }
+        }
+        let _ = self.revert_to(start_index);
+
+        is_figure
+    }
+
     /// parses a quote
     fn parse_quote(&mut self) -> ParseResult<Quote> {
         let start_index = self.index;
@@ -137,35 +355,263 @@ impl ParseBlock for Parser {
             }
         }
         let mut quote = Quote::new(metadata);
+        let mut lines: Vec<String> = Vec::new();
+        let mut line_starts: Vec<usize> = Vec::new();
+        let mut end_index = self.index;
 
-        while self.check_special(&QUOTE_START)
-            && self.next_char() != None
-            && (self.check_seek_inline_whitespace() || self.check_special(&LB))
-        {
-            if let Ok(text) = self.parse_text_line() {
-                if text.subtext.len() > 0 {
-                    quote.add_text(text);
+        while self.index < self.text.len() {
+            let line_start = self.index;
+            let mut line = String::new();
+            while self.index < self.text.len() && self.current_char != LB {
+                line.push(self.current_char);
+                self.skip_char();
+            }
+            let content = match line.trim_start().strip_prefix(QUOTE_START) {
+                Some(content)
+                    if content.is_empty()
+                        || content.starts_with(|c: char| c.is_whitespace() || c == QUOTE_START) =>
+                {
+                    content.strip_prefix(SPACE).unwrap_or(content)
                 }
-            } else {
+                _ => break,
+            };
+            line_starts.push(line_start + line.chars().count() - content.chars().count());
+            lines.push(content.to_string());
+            self.skip_char();
+            end_index = self.index;
+        }
+        if end_index < self.text.len() {
+            let _ = self.revert_to(end_index);
+        }
+        if !lines.is_empty() {
+            quote.content = self.parse_nested(lines.join("\n") + "\n", &line_starts);
+        }
+        if quote.content.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        quote.span = self.get_span(start_index);
+
+        Ok(quote)
+    }
+
+    /// parses a block that is wrapped in a `:::name` and a `:::` line
+    fn parse_container(&mut self) -> ParseResult<Block> {
+        self.seek_whitespace();
+        let start_index = self.index;
+        let container = self.read_container_header()?;
+        match container.name.as_str() {
+            "tabs" => {
+                let panes = self.parse_panes(&container, "tab", start_index)?;
+                let number = Arc::new(AtomicUsize::new(0));
+                self.document.add_tab_group(Arc::clone(&number));
+                return Ok(Block::Tabs(Tabs {
+                    metadata: container.metadata,
+                    panes: panes
+                        .into_iter()
+                        .map(|(pane, content)| TabPane {
+                            title: pane.title,
+                            content,
+                        })
+                        .collect(),
+                    number,
+                    span: self.get_span(start_index),
+                }));
+            }
+            "columns" => {
+                let panes = self.parse_panes(&container, "column", start_index)?;
+                return Ok(Block::Columns(Columns {
+                    metadata: container.metadata,
+                    columns: panes.into_iter().map(|(_, content)| content).collect(),
+                    span: self.get_span(start_index),
+                }));
+            }
+            _ => {}
+        }
+        let (content, line_starts) = match self.read_container_content(&container) {
+            Some(content) => content,
+            None => return Err(self.revert_with_error(start_index)),
+        };
+        let content = self.parse_nested(content, &line_starts);
+        let span = self.get_span(start_index);
+
+        match container.name.as_str() {
+            "left" | "center" | "right" | "justify" => Ok(Block::Aligned(Aligned {
+                alignment: get_alignment(&container.name),
+                content,
+                span,
+            })),
+            "details" => Ok(Block::Details(Details {
+                summary: container.title,
+                metadata: container.metadata,
+                content,
+                span,
+            })),
+            _ => Ok(Block::Admonition(Admonition {
+                kind: container.name,
+                title: container.title,
+                metadata: container.metadata,
+                content,
+                span,
+            })),
+        }
+    }
+
+    /// parses consecutive lines starting with `||` into a centered paragraph
+    fn parse_centered(&mut self) -> ParseResult<Aligned> {
+        self.seek_whitespace();
+        let start_index = self.index;
+        let mut paragraph = Paragraph::new();
+        while self.check_special_sequence(&SQ_CENTERED_START) {
+            self.skip_char();
+            self.seek_inline_whitespace();
+            paragraph.add_element(Line::Text(self.parse_text_line()?));
+            self.seek_inline_whitespace();
+        }
+        if paragraph.elements.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        paragraph.span = self.get_span(start_index);
+
+        Ok(Aligned {
+            alignment: Alignment::Center,
+            content: vec![Block::Paragraph(paragraph)],
+            span: self.get_span(start_index),
+        })
+    }
+
+    /// parses the panes of a container that only contains `:::name` containers
+    /// with the given name like the tabs of a group of tabs
+    fn parse_panes(
+        &mut self,
+        container: &Container,
+        pane_name: &str,
+        start_index: usize,
+    ) -> ParseResult<Vec<(Container, Vec<Block>)>> {
+        let mut panes = Vec::new();
+        loop {
+            self.seek_whitespace();
+            if self.index >= self.text.len() {
+                let diagnostic =
+                    unclosed_container_diagnostic(&container.name, container.span.clone());
+                self.document.diagnostics.add(diagnostic);
+                return Err(self.revert_with_error(start_index));
+            }
+            let line: String = self.text[self.index..]
+                .iter()
+                .take_while(|c| **c != LB)
+                .collect();
+            if is_container_end(&line) {
+                self.seek_until_linebreak();
                 break;
             }
+            let pane = match self.read_container_header() {
+                Ok(pane) if pane.name == pane_name => pane,
+                _ => return Err(self.revert_with_error(start_index)),
+            };
+            let (content, line_starts) = match self.read_container_content(&pane) {
+                Some(content) => content,
+                None => return Err(self.revert_with_error(start_index)),
+            };
+            let content = self.parse_nested(content, &line_starts);
+            panes.push((pane, content));
         }
-        if quote.text.len() == 0 {
+        if panes.is_empty() {
             return Err(self.revert_with_error(start_index));
         }
 
-        Ok(quote)
+        Ok(panes)
+    }
+
+    /// reads the `:::name[metadata] title` line that starts a container
+    fn read_container_header(&mut self) -> ParseResult<Container> {
+        let start_index = self.index;
+        self.assert_special_sequence(&SQ_CONTAINER, start_index)?;
+        self.skip_char();
+        let mut name = String::new();
+        while self.index < self.text.len()
+            && (self.current_char.is_alphanumeric()
+                || self.current_char == MINUS
+                || self.current_char == UNDERSCR)
+        {
+            name.push(self.current_char);
+            self.skip_char();
+        }
+        if name.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        let metadata = self.parse_inline_metadata().ok();
+        let span = self.get_span(start_index);
+        self.seek_inline_whitespace();
+        let title = if self.check_linebreak() {
+            self.skip_char();
+            None
+        } else {
+            self.parse_text_line()
+                .ok()
+                .filter(|title| !title.subtext.is_empty())
+        };
+
+        Ok(Container {
+            name,
+            metadata,
+            title,
+            span,
+        })
+    }
+
+    /// reads the raw lines of a container until the `:::` line that closes it
+    /// and returns them together with the index they start at
+    fn read_container_content(&mut self, container: &Container) -> Option<(String, Vec<usize>)> {
+        let mut lines: Vec<String> = Vec::new();
+        let mut line_starts: Vec<usize> = Vec::new();
+        let mut depth = 0;
+        let mut in_code_block = false;
+        loop {
+            if self.index >= self.text.len() {
+                let diagnostic =
+                    unclosed_container_diagnostic(&container.name, container.span.clone());
+                self.document.diagnostics.add(diagnostic);
+                return None;
+            }
+            let line_start = self.index;
+            let mut line = String::new();
+            while self.index < self.text.len() && self.current_char != LB {
+                line.push(self.current_char);
+                self.skip_char();
+            }
+            self.skip_char();
+            let trimmed = line.trim();
+            if trimmed.starts_with(&SQ_CODE_BLOCK.iter().collect::<String>()) {
+                in_code_block = !in_code_block;
+            } else if !in_code_block && is_container_end(trimmed) {
+                if depth == 0 {
+                    break;
+                }
+                depth -= 1;
+            } else if !in_code_block
+                && trimmed.starts_with(&SQ_CONTAINER.iter().collect::<String>())
+            {
+                depth += 1;
+            }
+            lines.push(line);
+            line_starts.push(line_start);
+        }
+
+        Some((lines.join("\n") + "\n", line_starts))
     }
 
     /// Parses a paragraph
     fn parse_paragraph(&mut self) -> ParseResult<Paragraph> {
         self.seek_whitespace();
+        let start_index = self.index;
         let mut paragraph = Paragraph::new();
         while let Ok(token) = self.parse_line() {
             paragraph.add_element(token);
+            self.skip_comments();
             let start_index = self.index;
             if self.check_special_sequence_group(&BLOCK_SPECIAL_CHARS)
                 || self.check_special_group(&self.block_break_at)
+                || self.check_image_figure()
             {
                 self.revert_to(start_index)?;
                 break;
@@ -176,6 +622,7 @@ impl ParseBlock for Parser {
         }
 
         if paragraph.elements.len() > 0 {
+            paragraph.span = self.get_span(start_index);
             Ok(paragraph)
         } else {
             Err(ParseError::new(self.index))
@@ -188,13 +635,17 @@ impl ParseBlock for Parser {
         let mut list = List::new();
         let start_index = self.index;
         self.seek_whitespace();
+        let mut line_start = self.index.min(self.text.len());
+        while line_start > start_index && self.text[line_start - 1] != LB {
+            line_start -= 1;
+        }
+        self.revert_to(line_start)?;
+        let marker: String = self.text[self.index..]
+            .iter()
+            .skip_while(|c| c.is_whitespace())
+            .take_while(|c| !c.is_whitespace())
+            .collect();
 
-        let ordered = if self.check_special_group(&LIST_SPECIAL_CHARS) {
-            false
-        } else {
-            true
-        };
-        list.ordered = ordered;
         let mut list_hierarchy: Vec<ListItem> = Vec::new();
         while let Ok(mut item) = self.parse_list_item() {
             while let Some(parent_item) = list_hierarchy.pop() {
@@ -224,6 +675,7 @@ impl ParseBlock for Parser {
                 }
             }
             list_hierarchy.push(item);
+            self.seek_list_continuation(&marker);
         }
 
         // the remaining items in the hierarchy need to be combined
@@ -238,8 +690,14 @@ impl ParseBlock for Parser {
             }
         }
         list.items.append(&mut list_hierarchy);
+        if let Some(first) = list.items.first() {
+            list.ordered = first.ordered;
+            list.style = first.style;
+            list.start = first.number;
+        }
 
         if list.items.len() > 0 {
+            list.span = self.get_span(start_index);
             Ok(list)
         } else {
             return Err(self.revert_with_error(start_index));
@@ -248,28 +706,151 @@ impl ParseBlock for Parser {
 
     /// parses a markdown table
     fn parse_table(&mut self) -> ParseResult<Table> {
-        let header = self.parse_row()?;
-        if self.check_linebreak() {
-            self.skip_char();
-        }
-        let seek_index = self.index;
-        let mut table = Table::new(header);
-        while let Some(_) = self.next_char() {
+        let start_index = self.index;
+        self.seek_whitespace();
+        let metadata = self.parse_inline_metadata().ok();
+        if metadata.is_some() {
+            // the metadata can be placed on its own line above the table
             self.seek_inline_whitespace();
-            if !self.check_special_group(&[MINUS, PIPE]) || self.check_linebreak() {
-                break;
+            if self.check_linebreak() {
+                self.skip_char();
             }
         }
+        let header = if let Ok(header) = self.parse_row() {
+            header
+        } else {
+            return Err(self.revert_with_error(start_index));
+        };
+        let mut table = Table::new(header);
+        table.metadata = metadata;
 
-        if !self.check_linebreak() {
-            self.revert_to(seek_index)?;
+        if !self.check_table_separator() {
+            table.span = self.get_span(start_index);
             return Ok(table);
         }
+        loop {
+            if let Ok(alignments) = self.parse_table_separator() {
+                table.set_alignments(alignments);
+                break;
+            }
+            if let Ok(row) = self.parse_row() {
+                table.add_header_row(row);
+            } else {
+                return Err(self.revert_with_error(start_index));
+            }
+        }
 
         self.seek_whitespace();
         while let Ok(row) = self.parse_row() {
             table.add_row(row);
         }
+        table.merge_cells();
+        table.span = self.get_span(start_index);
+
+        Ok(table)
+    }
+
+    /// checks if the following rows are terminated by a separator row
+    /// which means that they are header rows
+    fn check_table_separator(&mut self) -> bool {
+        let start_index = self.index;
+        let mut found = false;
+        loop {
+            let line = self.get_string_until(&[LB], &[]).unwrap_or_default();
+            let line = line.trim();
+            if is_table_separator(line) {
+                found = true;
+                break;
+            }
+            if !line.starts_with(PIPE) || !self.check_linebreak() {
+                break;
+            }
+            self.skip_char();
+        }
+        let _ = self.revert_to(start_index);
+
+        found
+    }
+
+    /// parses the separator between the header and the body of a table
+    /// and returns the alignment of each column
+    fn parse_table_separator(&mut self) -> ParseResult<Vec<Option<Alignment>>> {
+        let start_index = self.index;
+        let separator = self.get_string_until(&[LB], &[]).unwrap_or_default();
+        if !self.check_linebreak() || !is_table_separator(separator.trim()) {
+            return Err(self.revert_with_error(start_index));
+        }
+        let columns = separator.trim().trim_start_matches(PIPE);
+        let columns = columns.strip_suffix(PIPE).unwrap_or(columns);
+
+        Ok(columns.split(PIPE).map(Alignment::from_separator).collect())
+    }
+
+    /// parses the import of a csv or tsv file into a table
+    /// e.g. `<[data.csv][delimiter=";" columns="1,3" decimals=2]`
+    fn parse_data_import(&mut self) -> ParseResult<Table> {
+        let start_index = self.index;
+        self.seek_whitespace();
+        self.assert_special_sequence_group(&[&[IMPORT_START, IMPORT_OPEN]], start_index)?;
+        self.skip_char();
+        let path = self.get_string_until_or_revert(&[IMPORT_CLOSE], &[LB], start_index)?;
+        self.skip_char();
+        let metadata = self.parse_inline_metadata().ok();
+        let delimiter = if let Some(delimiter) = get_data_delimiter(&path, &metadata) {
+            delimiter
+        } else {
+            return Err(self.revert_with_error(start_index));
+        };
+        let span = self.get_span(start_index);
+        self.seek_inline_whitespace();
+        if self.check_linebreak() {
+            self.skip_char();
+        }
+        let mut records = match self.import_data(path, delimiter, span.clone()) {
+            Ok(records) => records.into_iter(),
+            Err(_) => return Err(self.revert_with_error(start_index)),
+        };
+
+        let meta = metadata.as_ref();
+        let has_header = !matches!(
+            meta.and_then(|m| m.data.get("header")),
+            Some(MetadataValue::Bool(false))
+        );
+        let header = if has_header { records.next() } else { None };
+        let records: Vec<Vec<String>> = records.collect();
+        let width = header
+            .iter()
+            .chain(records.iter())
+            .map(|r| r.len())
+            .max()
+            .unwrap_or(0);
+        let columns = if let Some(selection) = meta.and_then(|m| m.get_string("columns")) {
+            let (columns, unknown) = select_columns(&selection, header.as_ref(), width);
+            for column in unknown {
+                let mut diagnostic = Diagnostic::warning(
+                    D_UNKNOWN_COLUMN,
+                    format!("unknown column '{}'", column),
+                    span.clone(),
+                );
+                diagnostic.set_hint("columns are selected by their name or number starting at 1");
+                self.document.diagnostics.add(diagnostic);
+            }
+            columns
+        } else {
+            (0..width).collect()
+        };
+        let decimals = meta.and_then(|m| m.get_decimals(&span, &mut self.document.diagnostics));
+
+        let mut table = if let Some(header) = header {
+            Table::new(create_data_row(&header, &columns, None, &span))
+        } else {
+            Table::default()
+        };
+        records
+            .iter()
+            .for_each(|r| table.add_row(create_data_row(r, &columns, decimals, &span)));
+        table.metadata = metadata;
+        table.span = span;
 
         Ok(table)
     }
@@ -292,6 +873,13 @@ impl ParseBlock for Parser {
         if self.check_special(&IMPORT_CLOSE) {
             self.skip_char();
         }
+        let metadata_start = self.index;
+        let metadata = self.parse_inline_metadata().ok();
+        if get_data_delimiter(&path, &metadata).is_some() {
+            // data imports that failed to load are already reported by parse_data_import
+            return Err(self.revert_with_error(start_index));
+        }
+        let _ = self.revert_to(metadata_start);
         // parser success
 
         if self.section_nesting > 0 {
@@ -301,12 +889,151 @@ impl ParseBlock for Parser {
             return Err(err);
         }
 
+        let span = self.get_span(start_index);
         self.seek_whitespace();
 
-        if let Ok(anchor) = self.import_document(path.clone()) {
-            Ok(Import { path, anchor })
+        if let Ok(anchor) = self.import_document(path.clone(), span.clone()) {
+            Ok(Import { path, anchor, span })
         } else {
             Err(ParseError::new(self.index))
         }
     }
 }
+
+/// returns if the line consists only of pipes, dashes and colons
+fn is_table_separator(line: &str) -> bool {
+    line.starts_with(PIPE)
+        && line.contains(MINUS)
+        && line
+            .chars()
+            .all(|c| c == MINUS || c == PIPE || c == COLON || c.is_whitespace())
+}
+
+/// returns the caption that is defined in the metadata of a table
+fn get_metadata_caption(content: &FigureContent) -> Option<TextLine> {
+    if let FigureContent::Table(table) = content {
+        let caption = table.metadata.as_ref()?.get_string("caption")?;
+        let mut text = PlainText::new(caption);
+        text.span = table.span.clone();
+        let mut line = TextLine::new();
+        line.span = table.span.clone();
+        line.add_subtext(Inline::Plain(text));
+
+        Some(line)
+    } else {
+        None
+    }
+}
+
+/// returns the delimiter of a data import based on the file extension or
+/// the csv and tsv flags and the delimiter given in the metadata
+fn get_data_delimiter(path: &str, metadata: &Option<InlineMetadata>) -> Option<char> {
+    let extension = Path::new(path)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase());
+    let meta = metadata.as_ref();
+    let default = match extension.as_deref() {
+        Some("csv") => Some(','),
+        Some("tsv") => Some('\t'),
+        _ if meta.is_some_and(|m| m.get_bool("csv")) => Some(','),
+        _ if meta.is_some_and(|m| m.get_bool("tsv")) => Some('\t'),
+        _ => None,
+    }?;
+
+    match meta.and_then(|m| m.get_string("delimiter")).as_deref() {
+        Some("tab") | Some("\\t") => Some('\t'),
+        Some(delimiter) => Some(delimiter.chars().next().unwrap_or(default)),
+        None => Some(default),
+    }
+}
+
+/// returns the indices of the selected columns and the selected columns that don't exist
+/// Columns are selected by their name in the header or by their number starting at 1
+fn select_columns(
+    selection: &str,
+    header: Option<&Vec<String>>,
+    width: usize,
+) -> (Vec<usize>, Vec<String>) {
+    let mut columns = Vec::new();
+    let mut unknown = Vec::new();
+    for column in selection.split(COMMA).map(|c| c.trim()) {
+        let index = match column.parse::<usize>() {
+            Ok(number) if number > 0 && number <= width => Some(number - 1),
+            Ok(_) => None,
+            Err(_) => header.and_then(|h| h.iter().position(|name| name.trim() == column)),
+        };
+        if let Some(index) = index {
+            columns.push(index);
+        } else {
+            unknown.push(column.to_string());
+        }
+    }
+
+    (columns, unknown)
+}
+
+/// creates a row of a data import with the selected columns
+/// Numbers are rounded to the given number of decimals
+fn create_data_row(
+    record: &[String],
+    columns: &[usize],
+    decimals: Option<usize>,
+    span: &Span,
+) -> Row {
+    let mut row = Row::new();
+    for (column, index) in columns.iter().enumerate() {
+        let value = record.get(*index).map(|v| v.trim()).unwrap_or_default();
+        let value = match (decimals, value.parse::<f64>()) {
+            (Some(decimals), Ok(number)) => format!("{:.*}", decimals, number),
+            _ => value.to_string(),
+        };
+        let mut line = TextLine::new();
+        line.add_subtext(Inline::Plain(PlainText::new(value)));
+        let mut paragraph = Paragraph::new();
+        paragraph.add_element(Line::Text(line));
+        let mut cell = Cell::new(vec![Block::Paragraph(paragraph)]);
+        cell.column = column;
+        cell.span = span.clone();
+        row.add_cell(cell);
+    }
+
+    row
+}
+
+/// returns if the line closes a container
+fn is_container_end(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with(&SQ_CONTAINER.iter().collect::<String>())
+        && line.trim_start_matches(COLON).is_empty()
+}
+
+/// creates the diagnostic for a container without a closing `:::` line
+fn unclosed_container_diagnostic(name: &str, span: Span) -> Diagnostic {
+    let mut diagnostic = Diagnostic::warning(
+        D_UNCLOSED_CONTAINER,
+        format!("unclosed container '{}'", name),
+        span,
+    );
+    diagnostic.set_hint("containers are closed with a line that only contains `:::`");
+
+    diagnostic
+}
+
+/// returns the alignment of an alignment container with the given name
+fn get_alignment(name: &str) -> Alignment {
+    match name {
+        "left" => Alignment::Left,
+        "right" => Alignment::Right,
+        "justify" => Alignment::Justify,
+        _ => Alignment::Center,
+    }
+}
+
+/// returns the output format of a raw block with a language like `raw html`
+fn get_raw_format(language: &str) -> Option<String> {
+    let mut words = language.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some("raw"), Some(format), None) => Some(format.to_lowercase()),
+        _ => None,
+    }
+}
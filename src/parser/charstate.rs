@@ -16,14 +16,10 @@ pub trait CharStateMachine {
     fn check_special_group(&self, group: &[char]) -> bool;
     fn check_special_sequence(&mut self, sequence: &[char]) -> bool;
     fn check_special_sequence_group(&mut self, sequences: &[&[char]]) -> bool;
+    fn check_next_sequence(&self, sequence: &[char]) -> bool;
     fn check_linebreak(&self) -> bool;
     fn check_eof(&self) -> bool;
     fn assert_special(&mut self, character: &char, revert_index: usize) -> Result<(), ParseError>;
-    fn assert_special_group(
-        &mut self,
-        group: &[char],
-        revert_index: usize,
-    ) -> Result<(), ParseError>;
     fn assert_special_sequence(
         &mut self,
         sequence: &[char],
@@ -60,14 +56,7 @@ impl CharStateMachine for Parser {
         self.index += 1;
         self.previous_char = self.current_char;
         if (self.text.len() - 1) <= self.index {
-            for _ in 0..8 {
-                let mut buf = String::new();
-                if let Ok(_) = self.reader.read_line(&mut buf) {
-                    self.text.append(&mut buf.chars().collect())
-                } else {
-                    break;
-                }
-            }
+            self.read_lines(8);
         }
         self.current_char = *self.text.get(self.index)?;
 
@@ -185,12 +174,13 @@ impl CharStateMachine for Parser {
             self.revert_to(start_index).unwrap();
             return false;
         }
-        for sq_character in sequence {
+        for (index, sq_character) in sequence.iter().enumerate() {
             if self.current_char != *sq_character {
                 let _ = self.revert_to(start_index);
                 return false;
             }
-            if self.next_char() == None {
+            // the sequence may end with the last character of the text
+            if self.next_char() == None && index < sequence.len() - 1 {
                 let _ = self.revert_to(start_index);
                 return false;
             }
@@ -213,6 +203,12 @@ impl CharStateMachine for Parser {
         false
     }
 
+    /// checks if the text at the current position starts with a special sequence
+    /// without moving to the end of the sequence
+    fn check_next_sequence(&self, sequence: &[char]) -> bool {
+        !self.check_escaped() && self.text[self.index.min(self.text.len())..].starts_with(sequence)
+    }
+
     /// returns if the current character is a linebreak character
     /// Note: No one likes CRLF
     #[inline]
@@ -233,19 +229,6 @@ impl CharStateMachine for Parser {
         }
     }
 
-    #[inline]
-    fn assert_special_group(
-        &mut self,
-        group: &[char],
-        revert_index: usize,
-    ) -> Result<(), ParseError> {
-        if self.check_special_group(group) {
-            Ok(())
-        } else {
-            Err(self.revert_with_error(revert_index))
-        }
-    }
-
     fn assert_special_sequence(
         &mut self,
         sequence: &[char],
@@ -3,11 +3,16 @@ use crate::elements::tokens::*;
 use crate::elements::*;
 use crate::parser::block::ParseBlock;
 use crate::references::bibliography::BibReference;
-use crate::references::configuration::keys::BIB_REF_DISPLAY;
+use crate::references::configuration::keys::{BIB_REF_DISPLAY, FIGURE_DISPLAY};
+use crate::references::footnotes::FootnoteReference;
+use crate::references::glossary::GlossaryReference;
+use crate::references::index::IndexMarker;
+use crate::references::labels::LabelReference;
 use crate::references::templates::{GetTemplateVariables, Template, TemplateVariable};
 use crate::utils::parsing::{ParseError, ParseResult};
 use crate::Parser;
 use std::collections::HashMap;
+use std::sync::atomic::AtomicUsize;
 use std::sync::{Arc, RwLock};
 
 pub(crate) trait ParseInline {
@@ -21,10 +26,19 @@ pub(crate) trait ParseInline {
     fn parse_striked(&mut self) -> ParseResult<StrikedText>;
     fn parse_monospace(&mut self) -> ParseResult<MonospaceText>;
     fn parse_underlined(&mut self) -> ParseResult<UnderlinedText>;
+    fn parse_math(&mut self) -> ParseResult<Math>;
     fn parse_superscript(&mut self) -> ParseResult<SuperscriptText>;
     fn parse_emoji(&mut self) -> ParseResult<Emoji>;
     fn parse_colored(&mut self) -> ParseResult<Colored>;
     fn parse_bibref(&mut self) -> ParseResult<Arc<RwLock<BibReference>>>;
+    fn parse_label_reference(&mut self) -> ParseResult<Arc<RwLock<LabelReference>>>;
+    fn parse_footnote_reference(&mut self) -> ParseResult<Arc<RwLock<FootnoteReference>>>;
+    fn parse_glossary_reference(&mut self) -> ParseResult<Arc<RwLock<GlossaryReference>>>;
+    fn parse_index_marker(&mut self) -> ParseResult<Arc<RwLock<IndexMarker>>>;
+    fn parse_annotation(&mut self) -> ParseResult<Arc<RwLock<Annotation>>>;
+    fn skip_inline_comments(&mut self);
+    fn check_comment_start(&self) -> bool;
+    fn check_comment_end(&self) -> bool;
     fn parse_template_variable(&mut self) -> ParseResult<Arc<RwLock<TemplateVariable>>>;
     fn parse_plain(&mut self) -> ParseResult<PlainText>;
     fn parse_inline_metadata(&mut self) -> ParseResult<InlineMetadata>;
@@ -53,6 +67,7 @@ impl ParseInline for Parser {
                 return Ok(Inline::TemplateVar(var));
             }
         }
+        self.skip_inline_comments();
         if self.check_special(&PIPE) || self.check_linebreak() {
             Err(ParseError::new(self.index))
         } else if self.check_eof() {
@@ -71,6 +86,8 @@ impl ParseInline for Parser {
             Ok(Inline::Underlined(under))
         } else if let Ok(mono) = self.parse_monospace() {
             Ok(Inline::Monospace(mono))
+        } else if let Ok(math) = self.parse_math() {
+            Ok(Inline::Math(math))
         } else if let Ok(striked) = self.parse_striked() {
             Ok(Inline::Striked(striked))
         } else if let Ok(superscript) = self.parse_superscript() {
@@ -83,6 +100,16 @@ impl ParseInline for Parser {
             Ok(Inline::Colored(colored))
         } else if let Ok(bibref) = self.parse_bibref() {
             Ok(Inline::BibReference(bibref))
+        } else if let Ok(reference) = self.parse_label_reference() {
+            Ok(Inline::Reference(reference))
+        } else if let Ok(footnote) = self.parse_footnote_reference() {
+            Ok(Inline::Footnote(footnote))
+        } else if let Ok(reference) = self.parse_glossary_reference() {
+            Ok(Inline::GlossaryReference(reference))
+        } else if let Ok(marker) = self.parse_index_marker() {
+            Ok(Inline::IndexMarker(marker))
+        } else if let Ok(annotation) = self.parse_annotation() {
+            Ok(Inline::Annotation(annotation))
         } else {
             Ok(Inline::Plain(self.parse_plain()?))
         }
@@ -101,7 +128,22 @@ impl ParseInline for Parser {
             } else {
                 None
             };
-            Ok(Image { url, metadata })
+            let display = if metadata
+                .as_ref()
+                .and_then(|m| m.get_string("label"))
+                .is_some()
+            {
+                self.document.config.get_ref_entry(FIGURE_DISPLAY)
+            } else {
+                None
+            };
+            Ok(Image {
+                url,
+                metadata,
+                number: Arc::new(AtomicUsize::new(0)),
+                display,
+                span: self.get_span(start_index),
+            })
         } else {
             Err(self.revert_with_error(start_index))
         }
@@ -111,6 +153,7 @@ impl ParseInline for Parser {
     fn parse_url(&mut self, short_syntax: bool) -> ParseResult<Url> {
         let start_index = self.index;
         self.seek_inline_whitespace();
+        let span_start = self.index;
 
         let mut description = String::new();
         if self.check_special(&DESC_OPEN) {
@@ -135,11 +178,14 @@ impl ParseInline for Parser {
         };
         self.skip_char();
 
-        if description.is_empty() {
-            Ok(Url::new(None, url))
+        let mut url = if description.is_empty() {
+            Url::new(None, url)
         } else {
-            Ok(Url::new(Some(description), url))
-        }
+            Url::new(Some(description), url)
+        };
+        url.span = self.get_span(span_start);
+
+        Ok(url)
     }
 
     /// parses a markdown checkbox
@@ -158,7 +204,10 @@ impl ParseInline for Parser {
         self.assert_special(&CHECK_CLOSE, start_index)?;
         self.skip_char();
 
-        Ok(Checkbox { value: checked })
+        Ok(Checkbox {
+            value: checked,
+            span: self.get_span(start_index),
+        })
     }
 
     /// parses bold text with must start with two asterisks
@@ -172,18 +221,23 @@ impl ParseInline for Parser {
 
         Ok(BoldText {
             value: Box::new(inline),
+            span: self.get_span(start_index),
         })
     }
 
     fn parse_italic(&mut self) -> ParseResult<ItalicText> {
+        let start_index = self.index;
         Ok(ItalicText {
             value: Box::new(self.parse_surrounded(&ITALIC)?),
+            span: self.get_span(start_index),
         })
     }
 
     fn parse_striked(&mut self) -> ParseResult<StrikedText> {
+        let start_index = self.index;
         Ok(StrikedText {
             value: Box::new(self.parse_surrounded(&STRIKED)?),
+            span: self.get_span(start_index),
         })
     }
 
@@ -196,18 +250,48 @@ impl ParseInline for Parser {
         self.assert_special(&BACKTICK, start_index)?;
         self.skip_char();
 
-        Ok(MonospaceText { value: content })
+        Ok(MonospaceText {
+            value: content,
+            span: self.get_span(start_index),
+        })
     }
 
     fn parse_underlined(&mut self) -> ParseResult<UnderlinedText> {
+        let start_index = self.index;
         Ok(UnderlinedText {
             value: Box::new(self.parse_surrounded(&UNDERLINED)?),
+            span: self.get_span(start_index),
+        })
+    }
+
+    /// parses inline math surrounded by single dollar signs
+    /// The dollar signs must not be followed or preceded by whitespace on the inside
+    /// so that amounts of money aren't parsed as math
+    fn parse_math(&mut self) -> ParseResult<Math> {
+        let start_index = self.index;
+        self.assert_special(&MATH, start_index)?;
+        self.skip_char();
+        if self.current_char.is_whitespace() || self.check_special(&MATH) {
+            return Err(self.revert_with_error(start_index));
+        }
+        let expression = self.get_string_until_or_revert(&[MATH], &[LB], start_index)?;
+        self.assert_special(&MATH, start_index)?;
+        if expression.ends_with(char::is_whitespace) {
+            return Err(self.revert_with_error(start_index));
+        }
+        self.skip_char();
+
+        Ok(Math {
+            expression,
+            span: self.get_span(start_index),
         })
     }
 
     fn parse_superscript(&mut self) -> ParseResult<SuperscriptText> {
+        let start_index = self.index;
         Ok(SuperscriptText {
             value: Box::new(self.parse_surrounded(&SUPER)?),
+            span: self.get_span(start_index),
         })
     }
 
@@ -222,6 +306,7 @@ impl ParseInline for Parser {
             Ok(Emoji {
                 value: emoji_char,
                 name,
+                span: self.get_span(start_index),
             })
         } else {
             Err(self.revert_with_error(start_index))
@@ -242,6 +327,7 @@ impl ParseInline for Parser {
         Ok(Colored {
             value: Box::new(self.parse_inline()?),
             color,
+            span: self.get_span(start_index),
         })
     }
 
@@ -251,10 +337,10 @@ impl ParseInline for Parser {
         self.skip_char();
         let key = self.get_string_until_or_revert(&[BIBREF_CLOSE], &[SPACE, LB], start_index)?;
         self.skip_char();
-        let ref_entry = Arc::new(RwLock::new(BibReference::new(
-            key,
-            self.document.config.get_ref_entry(BIB_REF_DISPLAY),
-        )));
+        let mut bib_ref =
+            BibReference::new(key, self.document.config.get_ref_entry(BIB_REF_DISPLAY));
+        bib_ref.span = self.get_span(start_index);
+        let ref_entry = Arc::new(RwLock::new(bib_ref));
         self.document
             .bibliography
             .add_ref_entry(Arc::clone(&ref_entry));
@@ -262,6 +348,149 @@ impl ParseInline for Parser {
         Ok(ref_entry)
     }
 
+    /// parses a reference to a labelled element [@label]
+    fn parse_label_reference(&mut self) -> ParseResult<Arc<RwLock<LabelReference>>> {
+        let start_index = self.index;
+        self.assert_special_sequence(&SQ_LABEL_REF_START, start_index)?;
+        self.skip_char();
+        let key = self.get_string_until_or_revert(&[LABEL_REF_CLOSE], &[SPACE, LB], start_index)?;
+        self.assert_special(&LABEL_REF_CLOSE, start_index)?;
+        self.skip_char();
+        if key.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        let mut reference = LabelReference::new(key);
+        reference.span = self.get_span(start_index);
+        let reference = Arc::new(RwLock::new(reference));
+        self.document.labels.add_reference(Arc::clone(&reference));
+
+        Ok(reference)
+    }
+
+    /// parses a reference to a footnote [*key]
+    fn parse_footnote_reference(&mut self) -> ParseResult<Arc<RwLock<FootnoteReference>>> {
+        let start_index = self.index;
+        self.assert_special_sequence(&SQ_FOOTNOTE_START, start_index)?;
+        self.skip_char();
+        let key = self.get_string_until_or_revert(
+            &[FOOTNOTE_CLOSE],
+            &[SPACE, LB, FOOTNOTE_REF],
+            start_index,
+        )?;
+        self.assert_special(&FOOTNOTE_CLOSE, start_index)?;
+        self.skip_char();
+        if key.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        let mut reference = FootnoteReference::new(key);
+        reference.span = self.get_span(start_index);
+        let reference = Arc::new(RwLock::new(reference));
+        self.document
+            .footnotes
+            .add_reference(Arc::clone(&reference));
+
+        Ok(reference)
+    }
+
+    /// parses a reference to a glossary entry [~term]
+    fn parse_glossary_reference(&mut self) -> ParseResult<Arc<RwLock<GlossaryReference>>> {
+        let start_index = self.index;
+        self.assert_special_sequence(&SQ_GLOSSARY_START, start_index)?;
+        self.skip_char();
+        let key = self.get_string_until_or_revert(&[GLOSSARY_CLOSE], &[SPACE, LB], start_index)?;
+        self.assert_special(&GLOSSARY_CLOSE, start_index)?;
+        self.skip_char();
+        if key.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        let mut reference = GlossaryReference::new(key);
+        reference.span = self.get_span(start_index);
+        let reference = Arc::new(RwLock::new(reference));
+        self.document.glossary.add_reference(Arc::clone(&reference));
+
+        Ok(reference)
+    }
+
+    /// parses an index marker [#term!sub entry]
+    fn parse_index_marker(&mut self) -> ParseResult<Arc<RwLock<IndexMarker>>> {
+        let start_index = self.index;
+        self.assert_special_sequence(&SQ_INDEX_START, start_index)?;
+        self.skip_char();
+        let content = self.get_string_until_or_revert(&[INDEX_CLOSE], &[LB], start_index)?;
+        self.assert_special(&INDEX_CLOSE, start_index)?;
+        self.skip_char();
+        let path: Vec<String> = content
+            .split(INDEX_SUB_ENTRY)
+            .map(|t| t.trim().to_string())
+            .collect();
+        if path.iter().any(|t| t.is_empty()) {
+            return Err(self.revert_with_error(start_index));
+        }
+        let mut marker = IndexMarker::new(path);
+        marker.span = self.get_span(start_index);
+
+        Ok(Arc::new(RwLock::new(marker)))
+    }
+
+    /// parses a review annotation {>>[author=Name date=2020-05-01] text<<}
+    fn parse_annotation(&mut self) -> ParseResult<Arc<RwLock<Annotation>>> {
+        let start_index = self.index;
+        self.assert_special_sequence(&SQ_ANNOTATION_START, start_index)?;
+        self.skip_char();
+        let metadata = self.parse_inline_metadata().ok();
+        self.seek_inline_whitespace();
+        let text = self.get_string_until_sequence(&[&SQ_ANNOTATION_END], &[LB]);
+        let text = match text {
+            Ok(text) if self.index < self.text.len() => text,
+            _ => return Err(self.revert_with_error(start_index)),
+        };
+        self.skip_char();
+        let annotation = Arc::new(RwLock::new(Annotation {
+            text: text.trim().to_string(),
+            metadata,
+            visible: false,
+            span: self.get_span(start_index),
+        }));
+        self.document.add_annotation(Arc::clone(&annotation));
+
+        Ok(annotation)
+    }
+
+    /// skips comments /* comment */ that start and end in the current line
+    fn skip_inline_comments(&mut self) {
+        while self.check_comment_start() {
+            let start_index = self.index;
+            self.skip_char();
+            self.skip_char();
+            while !self.check_comment_end() {
+                if self.check_linebreak() || self.next_char().is_none() {
+                    let _ = self.revert_to(start_index);
+                    return;
+                }
+            }
+            self.skip_char();
+            self.skip_char();
+        }
+    }
+
+    /// checks if a comment starts at the current position
+    /// The `/*` has to be surrounded by whitespace so that paths like `src/*.rs` aren't comments
+    fn check_comment_start(&self) -> bool {
+        self.check_next_sequence(&SQ_COMMENT_START)
+            && (self.index == 0 || self.text[self.index - 1].is_whitespace())
+            && self
+                .text
+                .get(self.index + SQ_COMMENT_START.len())
+                .is_none_or(|c| c.is_whitespace())
+    }
+
+    /// checks if a comment ends at the current position with a `*/` following whitespace
+    fn check_comment_end(&self) -> bool {
+        self.check_next_sequence(&SQ_COMMENT_END)
+            && self.index > 0
+            && self.text[self.index - 1].is_whitespace()
+    }
+
     /// parses a template variable {prefix{name}suffix}
     fn parse_template_variable(&mut self) -> ParseResult<Arc<RwLock<TemplateVariable>>> {
         let start_index = self.index;
@@ -278,6 +507,7 @@ impl ParseInline for Parser {
             name,
             prefix,
             suffix,
+            span: self.get_span(start_index),
         })))
     }
 
@@ -286,12 +516,15 @@ impl ParseInline for Parser {
         if self.check_linebreak() {
             return Err(ParseError::new(self.index));
         }
+        let start_index = self.index;
         let mut characters = String::new();
         characters.push(self.current_char);
         while let Some(ch) = self.next_char() {
             if self.check_special_group(&INLINE_SPECIAL_CHARS)
                 || self.check_special_group(&self.inline_break_at)
                 || (self.parse_variables && self.check_special(&TEMP_VAR_OPEN))
+                || self.check_comment_start()
+                || self.check_next_sequence(&SQ_ANNOTATION_START)
             {
                 break;
             }
@@ -299,7 +532,10 @@ impl ParseInline for Parser {
         }
 
         if characters.len() > 0 {
-            Ok(PlainText { value: characters })
+            Ok(PlainText {
+                value: characters,
+                span: self.get_span(start_index),
+            })
         } else {
             Err(ParseError::new_with_message(
                 self.index,
@@ -367,8 +603,9 @@ impl ParseInline for Parser {
         let mut values = HashMap::new();
         while let Ok((key, value)) = self.parse_metadata_pair() {
             values.insert(key, value);
-            if self.check_special(&META_CLOSE) || self.check_linebreak() {
-                // abort the parser of the inner content when encountering a closing tag or linebreak
+            if self.check_special(&META_CLOSE) || self.check_linebreak() || self.check_eof() {
+                // abort the parser of the inner content when encountering a closing tag,
+                // linebreak or the end of the text
                 break;
             }
         }
@@ -403,7 +640,9 @@ impl ParseInline for Parser {
             None
         };
 
-        let placeholder = Arc::new(RwLock::new(Placeholder::new(name, metadata)));
+        let mut placeholder = Placeholder::new(name, metadata);
+        placeholder.span = self.get_span(start_index);
+        let placeholder = Arc::new(RwLock::new(placeholder));
         self.document.add_placeholder(Arc::clone(&placeholder));
 
         Ok(placeholder)
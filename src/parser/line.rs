@@ -1,8 +1,13 @@
 use crate::elements::tokens::*;
-use crate::elements::{Cell, Centered, Header, Line, ListItem, Row, Ruler, TextLine};
+use crate::elements::{
+    Block, Cell, Header, Inline, Line, ListItem, ListStyle, Paragraph, PlainText, Row, Ruler,
+    TextLine,
+};
 use crate::parser::charstate::CharStateMachine;
 use crate::parser::inline::ParseInline;
 use crate::references::bibliography::BibEntry;
+use crate::references::footnotes::Footnote;
+use crate::references::glossary::GlossaryEntry;
 use crate::utils::parsing::{ParseError, ParseResult};
 use crate::Parser;
 use std::sync::{Arc, RwLock};
@@ -11,11 +16,18 @@ pub(crate) trait ParseLine {
     fn parse_line(&mut self) -> ParseResult<Line>;
     fn parse_header(&mut self) -> ParseResult<Header>;
     fn parse_list_item(&mut self) -> ParseResult<ListItem>;
+    fn get_list_item_content(&mut self, indent: usize) -> Option<(String, Vec<usize>, bool)>;
+    fn seek_list_continuation(&mut self, marker: &str);
     fn parse_row(&mut self) -> ParseResult<Row>;
-    fn parse_centered(&mut self) -> ParseResult<Centered>;
+    fn parse_multiline_row(&mut self, start_index: usize) -> ParseResult<Row>;
+    fn check_row_continued(&self) -> bool;
+    fn check_merged_cell(&mut self) -> bool;
+    fn parse_formula(&mut self) -> ParseResult<String>;
     fn parse_ruler(&mut self) -> ParseResult<Ruler>;
     fn parse_text_line(&mut self) -> ParseResult<TextLine>;
     fn parse_bib_entry(&mut self) -> ParseResult<Arc<RwLock<BibEntry>>>;
+    fn parse_footnote(&mut self) -> ParseResult<Arc<RwLock<Footnote>>>;
+    fn parse_glossary_entry(&mut self) -> ParseResult<Arc<RwLock<GlossaryEntry>>>;
 }
 
 impl ParseLine for Parser {
@@ -26,8 +38,10 @@ impl ParseLine for Parser {
         } else {
             if let Ok(ruler) = self.parse_ruler() {
                 Ok(Line::Ruler(ruler))
-            } else if let Ok(centered) = self.parse_centered() {
-                Ok(Line::Centered(centered))
+            } else if let Ok(footnote) = self.parse_footnote() {
+                Ok(Line::Footnote(footnote))
+            } else if let Ok(entry) = self.parse_glossary_entry() {
+                Ok(Line::GlossaryEntry(entry))
             } else if let Ok(bib) = self.parse_bib_entry() {
                 Ok(Line::BibEntry(bib))
             } else if let Ok(text) = self.parse_text_line() {
@@ -55,12 +69,15 @@ impl ParseLine for Parser {
         let start_index = self.index;
         self.seek_inline_whitespace();
         let level = self.index - start_index;
-        self.assert_special_group(&LIST_SPECIAL_CHARS, start_index)?;
-        let ordered = self.current_char.is_numeric();
-        self.skip_char();
-        if self.check_special(&DOT) {
+        let mut marker = String::new();
+        while self.index < self.text.len() && !self.current_char.is_whitespace() {
+            marker.push(self.current_char);
             self.skip_char();
         }
+        let (style, number) = match get_list_marker(&marker) {
+            Some(marker) => marker,
+            None => return Err(self.revert_with_error(start_index)),
+        };
         if !self.check_seek_inline_whitespace() {
             return Err(self.revert_with_error(start_index));
         }
@@ -69,11 +86,94 @@ impl ParseLine for Parser {
             return Err(self.revert_with_error(start_index));
         }
 
-        let item = ListItem::new(self.parse_line()?, level as u16, ordered);
+        let indent = self.index - start_index;
+        let mut item = ListItem::new(self.parse_line()?, level as u16, style.is_some());
+        item.style = style.unwrap_or(ListStyle::Decimal);
+        item.number = number;
+        if let Some((content, line_starts, continued)) = self.get_list_item_content(indent) {
+            let blocks = self.parse_nested(content, &line_starts);
+            item.add_blocks(blocks, continued);
+        }
 
         Ok(item)
     }
 
+    /// returns the lines following a list item that are indented to the start of its text
+    /// together with the indices the lines start at and if they directly follow the item
+    fn get_list_item_content(&mut self, indent: usize) -> Option<(String, Vec<usize>, bool)> {
+        let start_index = self.index;
+        let mut end_index = start_index;
+        let mut lines: Vec<String> = Vec::new();
+        let mut line_starts: Vec<usize> = Vec::new();
+        let mut continued = None;
+        let mut blank_lines: Vec<usize> = Vec::new();
+        let mut in_code_block = false;
+
+        while self.index < self.text.len() {
+            let line_start = self.index;
+            let mut line = String::new();
+            while self.index < self.text.len() && self.current_char != LB {
+                line.push(self.current_char);
+                self.skip_char();
+            }
+            self.skip_char();
+            if line.trim().is_empty() {
+                blank_lines.push(line_start);
+                continue;
+            }
+            let line_indent = line.chars().take_while(|c| *c == SPACE).count();
+            let content: String = line.chars().skip(indent.min(line_indent)).collect();
+            if line_indent < indent || (!in_code_block && is_list_item(&content)) {
+                break;
+            }
+            if content.starts_with(&SQ_CODE_BLOCK.iter().collect::<String>()) {
+                in_code_block = !in_code_block;
+            }
+            if continued.is_none() {
+                continued = Some(blank_lines.is_empty());
+            } else {
+                lines.extend(blank_lines.iter().map(|_| String::new()));
+                line_starts.append(&mut blank_lines);
+            }
+            blank_lines.clear();
+            lines.push(content);
+            line_starts.push(line_start + indent);
+            end_index = self.index;
+        }
+        if end_index < self.text.len() {
+            let _ = self.revert_to(end_index);
+        }
+
+        continued.map(|continued| (lines.join("\n") + "\n", line_starts, continued))
+    }
+
+    /// skips the blank lines before the next item if the list continues
+    /// with a nested item or an item with the same kind of marker after them
+    fn seek_list_continuation(&mut self, marker: &str) {
+        let start_index = self.index;
+        let mut item_start = start_index;
+        let mut line = String::new();
+        while self.index < self.text.len() {
+            item_start = self.index;
+            line.clear();
+            while self.index < self.text.len() && self.current_char != LB {
+                line.push(self.current_char);
+                self.skip_char();
+            }
+            self.skip_char();
+            if !line.trim().is_empty() {
+                break;
+            }
+        }
+        let continued = item_start > start_index
+            && is_list_item(&line)
+            && (line.starts_with(SPACE) || is_same_list_marker(&line, marker));
+
+        if !continued || self.revert_to(item_start).is_err() {
+            let _ = self.revert_to(start_index);
+        }
+    }
+
     /// parses a table row/head
     fn parse_row(&mut self) -> ParseResult<Row> {
         let start_index = self.index;
@@ -83,36 +183,59 @@ impl ParseLine for Parser {
         if self.check_special(&PIPE) {
             return Err(self.revert_with_error(start_index));
         }
+        if self.check_row_continued() {
+            return self.parse_multiline_row(start_index);
+        }
         self.inline_break_at.push(PIPE);
 
         self.seek_inline_whitespace();
         let mut row = Row::new();
+        let mut column = 0;
         loop {
+            let cell_start = self.index;
+            let merged = self.check_merged_cell();
+            let formula = if merged {
+                None
+            } else {
+                self.parse_formula().ok()
+            };
             let mut element = TextLine::new();
-            while let Ok(inline) = self.parse_inline() {
-                element.subtext.push(inline);
-                if self.check_linebreak() || self.check_special(&PIPE) || self.check_eof() {
-                    break;
+            if let Some(formula) = &formula {
+                element.add_subtext(Inline::Plain(PlainText::new(format!("={}", formula))));
+            } else if !merged {
+                while let Ok(inline) = self.parse_inline() {
+                    element.subtext.push(inline);
+                    if self.check_linebreak() || self.check_special(&PIPE) || self.check_eof() {
+                        break;
+                    }
                 }
             }
-            row.add_cell(Cell {
-                text: Line::Text(element),
-            });
+            element.span = self.get_span(cell_start);
+            let mut paragraph = Paragraph::new();
+            paragraph.span = element.span.clone();
+            let span = element.span.clone();
+            paragraph.add_element(Line::Text(element));
+            let mut cell = Cell::new(vec![Block::Paragraph(paragraph)]);
+            cell.merged = merged;
+            cell.formula = formula;
+            cell.span = span;
+            cell.column = column;
             if self.check_special(&PIPE) {
                 self.skip_char();
+                while self.index < self.text.len() && self.check_special(&PIPE) {
+                    cell.colspan += 1;
+                    self.skip_char();
+                }
             }
+            column += cell.colspan;
+            row.add_cell(cell);
+            self.seek_inline_whitespace();
             if self.check_linebreak() || self.check_eof() {
                 break;
             }
-            self.seek_inline_whitespace();
         }
         self.inline_break_at.clear();
-        if self.check_special(&PIPE) {
-            self.skip_char();
-            self.skip_char();
-        } else {
-            self.skip_char();
-        }
+        self.skip_char();
 
         if row.cells.len() > 0 {
             Ok(row)
@@ -121,6 +244,116 @@ impl ParseLine for Parser {
         }
     }
 
+    /// parses a row with cells that span multiple lines
+    /// Every line except the last one ends with a backslash and the lines of a cell
+    /// are parsed as blocks
+    fn parse_multiline_row(&mut self, start_index: usize) -> ParseResult<Row> {
+        // the start index and text of every line and the colspan of every cell
+        let mut cells: Vec<(Vec<(usize, String)>, usize)> = Vec::new();
+        loop {
+            let line_start = self.index;
+            // the line is read manually because the line break after the backslash is escaped
+            let mut line = String::new();
+            while self.index < self.text.len() && self.current_char != LB {
+                line.push(self.current_char);
+                self.skip_char();
+            }
+            let line = line.trim_end();
+            let continued = line.ends_with(BACKSLASH);
+            let line = line.strip_suffix(BACKSLASH).unwrap_or(line);
+
+            for (index, (offset, text, colspan)) in split_row(line).into_iter().enumerate() {
+                if let Some((lines, _)) = cells.get_mut(index) {
+                    lines.push((line_start + offset, text));
+                } else {
+                    cells.push((vec![(line_start + offset, text)], colspan));
+                }
+            }
+            self.skip_char();
+            if !continued || self.check_eof() {
+                break;
+            }
+            let next_start = self.index;
+            self.seek_inline_whitespace();
+            if !self.check_special(&PIPE) {
+                self.revert_to(next_start)?;
+                break;
+            }
+            self.skip_char();
+        }
+        if cells.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+
+        let mut row = Row::new();
+        let mut column = 0;
+        for (lines, colspan) in cells {
+            let (index, first_line) = &lines[0];
+            let span = self.get_span_between(*index, index + first_line.chars().count());
+            let (content, line_starts) = join_cell_lines(&lines);
+            let mut cell = if content.chars().eq(SQ_MERGED_CELL.iter().cloned()) {
+                let mut cell = Cell::new(Vec::new());
+                cell.merged = true;
+                cell
+            } else if content.starts_with(EQ) && !content.contains(LB) {
+                let mut text = TextLine::new();
+                text.span = span.clone();
+                text.add_subtext(Inline::Plain(PlainText::new(content.clone())));
+                let mut paragraph = Paragraph::new();
+                paragraph.add_element(Line::Text(text));
+                let mut cell = Cell::new(vec![Block::Paragraph(paragraph)]);
+                cell.formula = Some(content[1..].trim().to_string());
+                cell
+            } else {
+                Cell::new(self.parse_nested(content + "\n", &line_starts))
+            };
+            cell.span = span;
+            cell.column = column;
+            cell.colspan = colspan;
+            column += colspan;
+            row.add_cell(cell);
+        }
+
+        Ok(row)
+    }
+
+    /// parses the formula of a table cell starting with = e.g. `=sum(B2:B5)`
+    fn parse_formula(&mut self) -> ParseResult<String> {
+        let start_index = self.index;
+        self.assert_special(&EQ, start_index)?;
+        self.skip_char();
+        let formula = self.get_string_until_or_revert(&[PIPE, LB], &[], start_index)?;
+
+        Ok(formula.trim().to_string())
+    }
+
+    /// returns if the rest of the current line ends with a backslash
+    /// which continues the table row on the next line
+    fn check_row_continued(&self) -> bool {
+        let line: String = self.text[self.index.min(self.text.len())..]
+            .iter()
+            .take_while(|c| **c != LB)
+            .collect();
+
+        line.trim_end().ends_with(BACKSLASH)
+    }
+
+    /// checks if the current cell only contains the ^^ marker to merge it
+    /// with the cell above and moves to the end of the cell if that's the case
+    fn check_merged_cell(&mut self) -> bool {
+        let start_index = self.index;
+        if self.check_special_sequence(&SQ_MERGED_CELL) {
+            self.skip_char();
+            self.seek_inline_whitespace();
+            if self.check_special(&PIPE) || self.check_linebreak() {
+                return true;
+            }
+        }
+        let _ = self.revert_to(start_index);
+
+        false
+    }
+
     fn parse_bib_entry(&mut self) -> ParseResult<Arc<RwLock<BibEntry>>> {
         let start_index = self.index;
         self.seek_inline_whitespace();
@@ -137,6 +370,8 @@ impl ParseLine for Parser {
             let url = self.get_string_until_or_revert(&[LB], &[], start_index)?;
             BibEntry::from_url(key, url, &self.document.config)
         };
+        let mut entry = entry;
+        entry.span = self.get_span(start_index);
         let entry_ref = Arc::new(RwLock::new(entry));
         self.document
             .bibliography
@@ -145,14 +380,58 @@ impl ParseLine for Parser {
         Ok(entry_ref)
     }
 
-    /// parses centered text
-    fn parse_centered(&mut self) -> ParseResult<Centered> {
+    /// parses a footnote definition [*key]: text
+    fn parse_footnote(&mut self) -> ParseResult<Arc<RwLock<Footnote>>> {
         let start_index = self.index;
-        self.assert_special_sequence(&SQ_CENTERED_START, start_index)?;
+        self.seek_inline_whitespace();
+        self.assert_special_sequence(&SQ_FOOTNOTE_START, start_index)?;
+        self.skip_char();
+        let key = self.get_string_until_or_revert(&[FOOTNOTE_CLOSE], &[LB, SPACE], start_index)?;
         self.skip_char();
-        let line = self.parse_text_line()?;
+        self.assert_special(&FOOTNOTE_DATA_START, start_index)?;
+        self.skip_char();
+        self.seek_inline_whitespace();
+        if key.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        let text = if let Ok(text) = self.parse_text_line() {
+            text
+        } else {
+            return Err(self.revert_with_error(start_index));
+        };
+        let mut footnote = Footnote::new(key, text);
+        footnote.span = self.get_span(start_index);
+        let footnote = Arc::new(RwLock::new(footnote));
+        self.document.footnotes.add_footnote(Arc::clone(&footnote));
 
-        Ok(Centered { line })
+        Ok(footnote)
+    }
+
+    /// parses a glossary entry [~term]:[long=".." description=".."]
+    fn parse_glossary_entry(&mut self) -> ParseResult<Arc<RwLock<GlossaryEntry>>> {
+        let start_index = self.index;
+        self.seek_inline_whitespace();
+        self.assert_special_sequence(&SQ_GLOSSARY_START, start_index)?;
+        self.skip_char();
+        let key = self.get_string_until_or_revert(&[GLOSSARY_CLOSE], &[LB, SPACE], start_index)?;
+        self.skip_char();
+        self.assert_special(&GLOSSARY_DATA_START, start_index)?;
+        self.skip_char();
+        self.seek_inline_whitespace();
+        if key.is_empty() {
+            return Err(self.revert_with_error(start_index));
+        }
+        let metadata = if let Ok(meta) = self.parse_inline_metadata() {
+            meta
+        } else {
+            return Err(self.revert_with_error(start_index));
+        };
+        let mut entry = GlossaryEntry::from_metadata(key, Box::new(metadata));
+        entry.span = self.get_span(start_index);
+        let entry = Arc::new(RwLock::new(entry));
+        self.document.glossary.add_entry(Arc::clone(&entry));
+
+        Ok(entry)
     }
 
     /// parses a ruler
@@ -161,11 +440,14 @@ impl ParseLine for Parser {
         self.seek_inline_whitespace();
         self.assert_special_sequence(&SQ_RULER, start_index)?;
         self.seek_until_linebreak();
-        Ok(Ruler {})
+        Ok(Ruler {
+            span: self.get_span(start_index),
+        })
     }
 
     /// Parses a line of text
     fn parse_text_line(&mut self) -> Result<TextLine, ParseError> {
+        let start_index = self.index;
         let mut text = TextLine::new();
         while let Ok(subtext) = self.parse_inline() {
             text.add_subtext(subtext);
@@ -174,6 +456,7 @@ impl ParseLine for Parser {
             }
         }
 
+        text.span = self.get_span(start_index);
         if self.check_linebreak() {
             self.skip_char();
         }
@@ -185,3 +468,174 @@ impl ParseLine for Parser {
         }
     }
 }
+
+/// returns if the line starts with the marker of a list item
+fn is_list_item(line: &str) -> bool {
+    let line = line.trim_start();
+    let marker = line.split_whitespace().next().unwrap_or_default();
+
+    line.len() > marker.len() && get_list_marker(marker).is_some()
+}
+
+/// returns if the line starts with a marker of the same kind as the given one
+fn is_same_list_marker(line: &str, marker: &str) -> bool {
+    let other = line.split_whitespace().next().unwrap_or_default();
+    match (get_list_marker(marker), get_list_marker(other)) {
+        (Some((None, _)), Some((None, _))) => marker == other,
+        (Some((Some(style), _)), Some((Some(other_style), _))) => style == other_style,
+        _ => false,
+    }
+}
+
+/// returns the numbering style and number of a list item marker
+/// The style is None for unordered items like `-`, ordered items are numbered
+/// with `1.`, `a)`, `A)`, `i)` or `I)`
+fn get_list_marker(marker: &str) -> Option<(Option<ListStyle>, usize)> {
+    let mut chars = marker.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        if LIST_SPECIAL_CHARS.contains(&c) {
+            return Some((None, 1));
+        }
+    }
+    if let Some(value) = marker.strip_suffix(L_PARENTH) {
+        // letters are closed with a parenthesis so that sentences starting
+        // with an initial like `A. Smith` aren't parsed as a list
+        return get_alphabetic_marker(value);
+    }
+    let value = marker.strip_suffix(DOT).unwrap_or(marker);
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if value.len() == marker.len() && value.len() > 1 {
+        // numbers with multiple digits need a dot so that text starting with a number
+        // isn't parsed as a list
+        return None;
+    }
+
+    value.parse().ok().map(|n| (Some(ListStyle::Decimal), n))
+}
+
+/// returns the alphabetic or roman numbering style and number of a marker without its parenthesis
+fn get_alphabetic_marker(value: &str) -> Option<(Option<ListStyle>, usize)> {
+    if value.is_empty() {
+        return None;
+    }
+    let lowercase = value.chars().all(|c| c.is_ascii_lowercase());
+    if !lowercase && !value.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    if value.len() == 1 && !value.eq_ignore_ascii_case("i") {
+        let number = (value.to_ascii_lowercase().as_bytes()[0] - b'a') as usize + 1;
+        let style = if lowercase {
+            ListStyle::LowerAlpha
+        } else {
+            ListStyle::UpperAlpha
+        };
+        return Some((Some(style), number));
+    }
+    let style = if lowercase {
+        ListStyle::LowerRoman
+    } else {
+        ListStyle::UpperRoman
+    };
+
+    parse_roman_numeral(value).map(|n| (Some(style), n))
+}
+
+/// parses a roman numeral like `xiv`
+fn parse_roman_numeral(value: &str) -> Option<usize> {
+    let digits = value
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            'i' => Some(1),
+            'v' => Some(5),
+            'x' => Some(10),
+            'l' => Some(50),
+            'c' => Some(100),
+            'd' => Some(500),
+            'm' => Some(1000),
+            _ => None,
+        })
+        .collect::<Option<Vec<isize>>>()?;
+    let mut number = 0;
+    for (index, digit) in digits.iter().enumerate() {
+        if digits.get(index + 1).is_some_and(|next| next > digit) {
+            number -= digit;
+        } else {
+            number += digit;
+        }
+    }
+
+    if number > 0 {
+        Some(number as usize)
+    } else {
+        None
+    }
+}
+
+/// splits the content of a table row without the leading pipe into its cells
+/// and returns the offset, text and colspan of every cell
+fn split_row(line: &str) -> Vec<(usize, String, usize)> {
+    let mut cells: Vec<(usize, String, usize)> = Vec::new();
+    let mut text = String::new();
+    let mut offset = 0;
+    let mut escaped = false;
+    for (index, ch) in line.chars().enumerate() {
+        if ch == PIPE && !escaped {
+            if text.is_empty() && !cells.is_empty() {
+                cells.last_mut().unwrap().2 += 1;
+            } else {
+                cells.push((offset, std::mem::take(&mut text), 1));
+            }
+            offset = index + 1;
+        } else {
+            text.push(ch);
+        }
+        escaped = ch == BACKSLASH && !escaped;
+    }
+    if !text.trim().is_empty() {
+        cells.push((offset, text, 1));
+    }
+
+    cells
+}
+
+/// joins the lines of a multiline cell without their common indentation and surrounding blank lines
+/// and returns the text together with the indices the joined lines start at
+fn join_cell_lines(lines: &[(usize, String)]) -> (String, Vec<usize>) {
+    let indent = lines
+        .iter()
+        .filter(|(_, l)| !l.trim().is_empty())
+        .map(|(_, l)| l.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+    let lines: Vec<(usize, String)> = lines
+        .iter()
+        .map(|(start, l)| {
+            let line = l.trim_end();
+            (
+                start + indent.min(line.chars().count()),
+                line.chars().skip(indent).collect(),
+            )
+        })
+        .collect();
+    let first = lines.iter().position(|(_, l)| !l.is_empty());
+    let last = lines.iter().rposition(|(_, l)| !l.is_empty());
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return (String::new(), vec![lines[0].0]),
+    };
+    let mut lines = lines[first..=last].to_vec();
+    let leading = lines[0].1.chars().take_while(|c| c.is_whitespace()).count();
+    lines[0].0 += leading;
+    lines[0].1 = lines[0].1.trim_start().to_string();
+
+    (
+        lines
+            .iter()
+            .map(|(_, l)| l.as_str())
+            .collect::<Vec<&str>>()
+            .join("\n"),
+        lines.iter().map(|(start, _)| *start).collect(),
+    )
+}
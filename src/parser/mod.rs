@@ -4,12 +4,16 @@ pub(crate) mod inline;
 pub(crate) mod line;
 
 use self::block::ParseBlock;
-use crate::elements::{Document, ImportAnchor};
-use crate::references::configuration::Configuration;
+use self::charstate::CharStateMachine;
+use crate::elements::tokens::{BLOCK_SPECIAL_CHARS, LB};
+use crate::elements::{Block, Document, ImportAnchor, Span};
+use crate::references::configuration::keys::REVIEW_MODE;
+use crate::references::configuration::{Configuration, Value};
+use crate::utils::csv::read_records;
+use crate::utils::diagnostics::{Diagnostic, D_IMPORT_CYCLIC, D_IMPORT_MISSING, D_PARSE_ERROR};
 use crate::utils::parsing::{ParseError, ParseResult};
-use colored::*;
 use crossbeam_utils::sync::WaitGroup;
-use std::fs::File;
+use std::fs::{read_to_string, File};
 use std::io;
 use std::io::{BufRead, BufReader, Cursor};
 use std::path::PathBuf;
@@ -23,7 +27,7 @@ pub struct Parser {
     section_nesting: u8,
     sections: Vec<u8>,
     section_return: Option<u8>,
-    path: Option<PathBuf>,
+    path: Option<Arc<PathBuf>>,
     paths: Arc<Mutex<Vec<PathBuf>>>,
     wg: WaitGroup,
     is_child: bool,
@@ -33,6 +37,11 @@ pub struct Parser {
     pub(crate) previous_char: char,
     pub(crate) reader: Box<dyn BufRead>,
     pub(crate) parse_variables: bool,
+    line_starts: Vec<(usize, usize)>,
+    text_bytes: usize,
+    /// the line, column and byte offset in the source file of every line
+    /// for parsers of nested content whose lines are taken from different positions
+    source_lines: Vec<(usize, usize, usize)>,
 }
 
 impl Parser {
@@ -92,33 +101,20 @@ impl Parser {
         path: Option<PathBuf>,
         paths: Arc<Mutex<Vec<PathBuf>>>,
         is_child: bool,
-        mut reader: Box<dyn BufRead>,
+        reader: Box<dyn BufRead>,
     ) -> Self {
         if let Some(path) = path.clone() {
             paths.lock().unwrap().push(path.clone())
         }
-        let mut text = Vec::new();
-        let mut current_char = ' ';
-        for _ in 0..8 {
-            let mut buf = String::new();
-            if let Ok(_) = reader.read_line(&mut buf) {
-                text.append(&mut buf.chars().collect::<Vec<char>>());
-            } else {
-                break;
-            }
-        }
-        if let Some(ch) = text.get(0) {
-            current_char = *ch
-        }
         let document = Document::new(!is_child);
-        Self {
+        let mut parser = Self {
             index: 0,
-            text,
-            current_char,
+            text: Vec::new(),
+            current_char: ' ',
             sections: Vec::new(),
             section_nesting: 0,
             section_return: None,
-            path,
+            path: path.map(Arc::new),
             paths,
             wg: WaitGroup::new(),
             is_child,
@@ -128,18 +124,139 @@ impl Parser {
             document,
             reader,
             parse_variables: false,
+            line_starts: vec![(0, 0)],
+            text_bytes: 0,
+            source_lines: Vec::new(),
+        };
+        parser.read_lines(8);
+        if let Some(ch) = parser.text.first() {
+            parser.current_char = *ch
+        }
+
+        parser
+    }
+
+    /// Reads the given number of lines from the reader into the text buffer
+    pub(crate) fn read_lines(&mut self, count: usize) {
+        for _ in 0..count {
+            let mut buf = String::new();
+            if let Ok(_) = self.reader.read_line(&mut buf) {
+                for ch in buf.chars() {
+                    if self.text.last() == Some(&LB) {
+                        self.line_starts.push((self.text.len(), self.text_bytes));
+                    }
+                    self.text_bytes += ch.len_utf8();
+                    self.text.push(ch);
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns the span from the given index to the current index
+    /// excluding surrounding whitespace
+    pub(crate) fn get_span(&self, start_index: usize) -> Span {
+        self.get_span_between(start_index, self.index)
+    }
+
+    /// Returns the span between two indices excluding surrounding whitespace
+    pub(crate) fn get_span_between(&self, start_index: usize, end_index: usize) -> Span {
+        let mut start = start_index.min(self.text.len());
+        let mut end = end_index.min(self.text.len()).max(start);
+        while start < end && self.text[start].is_whitespace() {
+            start += 1;
+        }
+        while end > start && self.text[end - 1].is_whitespace() {
+            end -= 1;
+        }
+        let (line, column, start_byte) = self.get_position(start);
+        let (_, _, end_byte) = self.get_position(end);
+
+        Span {
+            path: self.path.clone(),
+            start: start_byte,
+            end: end_byte,
+            line,
+            column,
+        }
+    }
+
+    /// Returns the text of a line starting at 1 without the linebreak
+    fn get_line(&self, line: usize) -> Option<String> {
+        let (start, _) = *self.line_starts.get(line - 1)?;
+        Some(
+            self.text[start..]
+                .iter()
+                .take_while(|c| **c != LB)
+                .collect(),
+        )
+    }
+
+    /// Adds the source line to all diagnostics that don't have one
+    fn add_diagnostic_snippets(&mut self) {
+        let mut diagnostics = std::mem::take(&mut self.document.diagnostics);
+        for diagnostic in diagnostics.iter_mut() {
+            if diagnostic.snippet.is_some() || diagnostic.span.line == 0 {
+                continue;
+            }
+            let line = diagnostic.span.line;
+            if diagnostic.span.path == self.path {
+                diagnostic.snippet = self.get_line(line);
+            } else if let Some(path) = &diagnostic.span.path {
+                diagnostic.snippet = self
+                    .document
+                    .sources
+                    .get(path.as_ref())
+                    .and_then(|lines| lines.get(line - 1))
+                    .cloned();
+            }
         }
+        self.document.diagnostics = diagnostics;
+    }
+
+    /// Returns the line, column and byte offset of a character index
+    fn get_position(&self, index: usize) -> (usize, usize, usize) {
+        let line = match self
+            .line_starts
+            .binary_search_by_key(&index, |(start, _)| *start)
+        {
+            Ok(line) => line,
+            Err(line) => line - 1,
+        };
+        let (line_start, line_byte) = self.line_starts[line];
+        let byte = line_byte
+            + self.text[line_start..index]
+                .iter()
+                .map(|c| c.len_utf8())
+                .sum::<usize>();
+        if self.source_lines.is_empty() {
+            return (line + 1, index - line_start + 1, byte);
+        }
+        // lines after the mapped ones are located relative to the last mapped line
+        let mapped = line.min(self.source_lines.len() - 1);
+        let (source_line, source_column, source_byte) = self.source_lines[mapped];
+        let (_, mapped_byte) = self.line_starts[mapped];
+        let column = if mapped == line {
+            source_column + index - line_start
+        } else {
+            index - line_start + 1
+        };
+
+        (
+            source_line + line - mapped,
+            column,
+            source_byte + byte - mapped_byte,
+        )
     }
 
     pub fn set_config(&mut self, config: Configuration) {
         self.document.config = config;
     }
 
-    /// Returns the text of the parser as a string
-    fn get_text(&self) -> String {
-        self.text
-            .iter()
-            .fold("".to_string(), |a, b| format!("{}{}", a, b))
+    /// Sets if the review annotations of the document are rendered
+    pub fn set_review_mode(&mut self, enabled: bool) {
+        self.document.config.set(REVIEW_MODE, Value::Bool(enabled));
     }
 
     /// Returns the import paths of the parser
@@ -162,34 +279,49 @@ impl Parser {
         path
     }
 
-    /// starts up a new thread to parse the imported document
-    fn import_document(&mut self, path: String) -> ParseResult<Arc<RwLock<ImportAnchor>>> {
+    /// resolves the path of an import and adds a diagnostic if the file doesn't exist
+    fn resolve_import_path(&mut self, path: String, span: Span) -> ParseResult<PathBuf> {
         let path = self.transform_path(path);
         if !path.exists() || !path.is_file() {
-            println!(
-                "{}",
+            let mut diagnostic = Diagnostic::error(
+                D_IMPORT_MISSING,
                 format!(
                     "Import of \"{}\" failed: The file doesn't exist.",
                     path.to_str().unwrap()
-                )
-                .red()
+                ),
+                span,
             );
+            diagnostic.set_hint("import paths are relative to the importing file");
+            self.document.diagnostics.add(diagnostic);
             return Err(ParseError::new_with_message(
                 self.index,
                 "file does not exist",
             ));
         }
+
+        Ok(path)
+    }
+
+    /// starts up a new thread to parse the imported document
+    fn import_document(
+        &mut self,
+        path: String,
+        span: Span,
+    ) -> ParseResult<Arc<RwLock<ImportAnchor>>> {
+        let path = self.resolve_import_path(path, span.clone())?;
         {
             let mut paths = self.paths.lock().unwrap();
             if paths.iter().find(|item| **item == path) != None {
-                println!(
-                    "{}",
+                let mut diagnostic = Diagnostic::warning(
+                    D_IMPORT_CYCLIC,
                     format!(
                         "Import of \"{}\" failed: Cyclic import.",
                         path.to_str().unwrap()
-                    )
-                    .yellow()
+                    ),
+                    span,
                 );
+                diagnostic.set_hint("every file can only be imported once");
+                self.document.diagnostics.add(diagnostic);
                 return Err(ParseError::new_with_message(self.index, "cyclic import"));
             }
             paths.push(path.clone());
@@ -212,52 +344,137 @@ impl Parser {
         Ok(anchor)
     }
 
-    /// parses the given text into a document
-    pub fn parse(&mut self) -> Document {
-        self.document.path = if let Some(path) = &self.path {
-            Some(path.canonicalize().unwrap().to_str().unwrap().to_string())
-        } else {
-            None
+    /// reads the records of an imported csv or tsv file
+    /// The file is added to the import paths so that it is watched for changes
+    /// but unlike documents it can be imported multiple times
+    pub(crate) fn import_data(
+        &mut self,
+        path: String,
+        delimiter: char,
+        span: Span,
+    ) -> ParseResult<Vec<Vec<String>>> {
+        let path = self.resolve_import_path(path, span.clone())?;
+        let text = match read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                self.document.diagnostics.add(Diagnostic::error(
+                    D_IMPORT_MISSING,
+                    format!("Import of \"{}\" failed: {}", path.to_str().unwrap(), e),
+                    span,
+                ));
+                return Err(ParseError::new_with_message(
+                    self.index,
+                    "failed to read file",
+                ));
+            }
         };
+        {
+            let mut paths = self.paths.lock().unwrap();
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
 
+        Ok(read_records(&text, delimiter))
+    }
+
+    /// parses text that is nested inside an element into blocks that belong to the current document
+    /// The line starts are the indices the lines of the text start at in the current text
+    pub(crate) fn parse_nested(&mut self, text: String, line_starts: &[usize]) -> Vec<Block> {
+        let mut parser = Self::create(
+            None,
+            Arc::clone(&self.paths),
+            true,
+            Box::new(Cursor::new(text.into_bytes())),
+        );
+        parser.source_lines = line_starts
+            .iter()
+            .map(|start| self.get_position((*start).min(self.text.len())))
+            .collect();
+        parser.path = self.path.clone();
+        parser.wg = self.wg.clone();
+        parser.document.config = self.document.config.clone();
+        let blocks = parser.parse_blocks();
+        self.document.combine_references(&mut parser.document);
+
+        blocks
+    }
+
+    /// parses blocks until the end of the text is reached
+    fn parse_blocks(&mut self) -> Vec<Block> {
+        let mut blocks = Vec::new();
         while self.index < self.text.len() {
             match self.parse_block() {
-                Ok(block) => self.document.add_element(block),
+                Ok(block) => blocks.push(block),
                 Err(err) => {
                     if err.eof {
                         break;
                     }
-                    if let Some(path) = &self.path {
-                        if let Some(position) = err.get_position(&self.get_text()) {
-                            println!(
-                                "{}",
-                                format!(
-                                    "Error in File {}:{}:{} - {}",
-                                    path.to_str().unwrap(),
-                                    position.0,
-                                    position.1,
-                                    err
-                                )
-                                .red()
-                            );
-                        } else {
-                            println!(
-                                "{}",
-                                format!("Error in File {}: {}", path.to_str().unwrap(), err).red()
-                            );
-                        }
-                    } else {
-                        println!("{}", err);
+                    if self.check_remaining_whitespace() {
+                        break;
                     }
-                    break;
+                    let index = err.get_index();
+                    self.document.diagnostics.add(Diagnostic::error(
+                        D_PARSE_ERROR,
+                        err.get_message()
+                            .unwrap_or_else(|| "failed to parse block".to_string()),
+                        self.get_span_between(index, index + 1),
+                    ));
+                    self.recover();
                 }
             }
         }
 
+        blocks
+    }
+
+    /// Skips to the next blank line or the start of a block
+    /// so that parsing can continue after a block failed to parse
+    fn recover(&mut self) {
+        self.seek_until_linebreak();
+        while !self.check_eof() {
+            let line_start = self.index;
+            self.seek_inline_whitespace();
+            let block_start =
+                self.check_linebreak() || self.check_special_sequence_group(&BLOCK_SPECIAL_CHARS);
+            let _ = self.revert_to(line_start);
+            if block_start {
+                return;
+            }
+            self.seek_until_linebreak();
+        }
+    }
+
+    /// Returns if there is only whitespace left to parse
+    fn check_remaining_whitespace(&mut self) -> bool {
+        self.read_lines(8);
+        self.index >= self.text.len() || self.text[self.index..].iter().all(|c| c.is_whitespace())
+    }
+
+    /// parses the given text into a document
+    pub fn parse(&mut self) -> Document {
+        self.document.path = if let Some(path) = &self.path {
+            Some(path.canonicalize().unwrap().to_str().unwrap().to_string())
+        } else {
+            None
+        };
+
+        let mut blocks = self.parse_blocks();
+        self.document.elements.append(&mut blocks);
+
         let wg = self.wg.clone();
         self.wg = WaitGroup::new();
         wg.wait();
+        if self.is_child {
+            if let Some(path) = &self.path {
+                let lines = (1..=self.line_starts.len())
+                    .filter_map(|line| self.get_line(line))
+                    .collect();
+                self.document.sources.insert((**path).clone(), lines);
+            }
+        }
         self.document.post_process();
+        self.add_diagnostic_snippets();
         let document = self.document.clone();
         self.document = Document::new(!self.is_child);
 
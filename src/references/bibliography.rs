@@ -1,4 +1,4 @@
-use crate::elements::Metadata;
+use crate::elements::{Metadata, Span};
 use crate::format::PlaceholderTemplate;
 use crate::references::configuration::keys::{BIB_DISPLAY, BIB_HIDE_UNUSED};
 use crate::references::configuration::{ConfigRefEntry, Configuration, Value};
@@ -26,6 +26,7 @@ pub struct BibEntry {
     pub notes: Option<String>,
     pub display: Option<ConfigRefEntry>,
     pub hide_unused: Option<ConfigRefEntry>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -33,6 +34,7 @@ pub struct BibReference {
     pub(crate) key: String,
     pub(crate) reference_entry: Option<Arc<RwLock<BibEntry>>>,
     pub(crate) display: Option<ConfigRefEntry>,
+    pub(crate) span: Span,
 }
 
 #[derive(Clone, Debug)]
@@ -81,6 +83,7 @@ impl BibEntry {
             notes: data.get_string(B_NOTES),
             display: config.get_ref_entry(BIB_DISPLAY),
             hide_unused: config.get_ref_entry(BIB_HIDE_UNUSED),
+            span: Span::default(),
         }
     }
 
@@ -97,6 +100,7 @@ impl BibEntry {
             notes: None,
             display: config.get_ref_entry(BIB_DISPLAY),
             hide_unused: config.get_ref_entry(BIB_HIDE_UNUSED),
+            span: Span::default(),
         }
     }
 
@@ -128,6 +132,7 @@ impl BibReference {
             key: key.to_string(),
             display,
             reference_entry: None,
+            span: Span::default(),
         }
     }
 
@@ -182,6 +187,15 @@ impl Bibliography {
         });
     }
 
+    /// returns all references that couldn't be assigned to an entry
+    pub(crate) fn get_unresolved_references(&self) -> Vec<Arc<RwLock<BibReference>>> {
+        self.references
+            .iter()
+            .filter(|r| r.read().unwrap().reference_entry.is_none())
+            .cloned()
+            .collect()
+    }
+
     pub fn add_ref_entry(&mut self, entry: Arc<RwLock<BibReference>>) {
         self.references.push(entry)
     }
@@ -4,6 +4,9 @@ use serde_derive::{Deserialize, Serialize};
 pub struct RootConfig {
     pub(crate) bibliography: Option<BibConfig>,
     pub(crate) metadata: Option<MetaConfig>,
+    pub(crate) numbering: Option<NumberingConfig>,
+    pub(crate) footnotes: Option<FootnoteConfig>,
+    pub(crate) review: Option<ReviewConfig>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -13,6 +16,25 @@ pub struct BibConfig {
     pub(crate) hide_unused: Option<bool>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NumberingConfig {
+    pub(crate) section_display: Option<String>,
+    pub(crate) figure_display: Option<String>,
+    pub(crate) table_display: Option<String>,
+    pub(crate) listing_display: Option<String>,
+    pub(crate) quote_display: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FootnoteConfig {
+    pub(crate) placement: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReviewConfig {
+    pub(crate) enabled: Option<bool>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MetaConfig {
     pub(crate) author: Option<String>,
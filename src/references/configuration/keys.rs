@@ -2,6 +2,16 @@ pub const BIB_DISPLAY: &str = "bib-entry-display";
 pub const BIB_REF_DISPLAY: &str = "bib-ref-display";
 pub const BIB_HIDE_UNUSED: &str = "bib-hide-unused";
 
+pub const SECTION_DISPLAY: &str = "section-display";
+pub const FIGURE_DISPLAY: &str = "figure-display";
+pub const TABLE_DISPLAY: &str = "table-display";
+pub const LISTING_DISPLAY: &str = "listing-display";
+pub const QUOTE_DISPLAY: &str = "quote-display";
+
+pub const FOOTNOTE_PLACEMENT: &str = "footnote-placement";
+
 pub const META_AUTHOR: &str = "author";
 pub const META_TITLE: &str = "title";
 pub const META_DATE: &str = "date";
+
+pub const REVIEW_MODE: &str = "review-mode";
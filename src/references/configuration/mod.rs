@@ -1,7 +1,9 @@
 use crate::elements::MetadataValue;
 use crate::references::configuration::config::RootConfig;
 use crate::references::configuration::keys::{
-    BIB_DISPLAY, BIB_HIDE_UNUSED, BIB_REF_DISPLAY, META_AUTHOR, META_DATE, META_TITLE,
+    BIB_DISPLAY, BIB_HIDE_UNUSED, BIB_REF_DISPLAY, FIGURE_DISPLAY, FOOTNOTE_PLACEMENT,
+    LISTING_DISPLAY, META_AUTHOR, META_DATE, META_TITLE, QUOTE_DISPLAY, REVIEW_MODE,
+    SECTION_DISPLAY, TABLE_DISPLAY,
 };
 use crate::references::templates::Template;
 use std::collections::HashMap;
@@ -84,6 +86,33 @@ impl Configuration {
                 self.set(BIB_HIDE_UNUSED, Value::Bool(*cfg));
             }
         }
+        if let Some(numbering) = &config.numbering {
+            if let Some(cfg) = &numbering.section_display {
+                self.set(SECTION_DISPLAY, Value::String(cfg.clone()))
+            }
+            if let Some(cfg) = &numbering.figure_display {
+                self.set(FIGURE_DISPLAY, Value::String(cfg.clone()))
+            }
+            if let Some(cfg) = &numbering.table_display {
+                self.set(TABLE_DISPLAY, Value::String(cfg.clone()))
+            }
+            if let Some(cfg) = &numbering.listing_display {
+                self.set(LISTING_DISPLAY, Value::String(cfg.clone()))
+            }
+            if let Some(cfg) = &numbering.quote_display {
+                self.set(QUOTE_DISPLAY, Value::String(cfg.clone()))
+            }
+        }
+        if let Some(footnotes) = &config.footnotes {
+            if let Some(cfg) = &footnotes.placement {
+                self.set(FOOTNOTE_PLACEMENT, Value::String(cfg.clone()))
+            }
+        }
+        if let Some(review) = &config.review {
+            if let Some(cfg) = &review.enabled {
+                self.set(REVIEW_MODE, Value::Bool(*cfg))
+            }
+        }
         if let Some(meta) = &config.metadata {
             if let Some(cfg) = &meta.author {
                 self.set(META_AUTHOR, Value::String(cfg.clone()))
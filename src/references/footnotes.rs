@@ -0,0 +1,137 @@
+use crate::elements::*;
+use crate::references::configuration::keys::FOOTNOTE_PLACEMENT;
+use crate::utils::diagnostics::{Diagnostic, D_UNRESOLVED_FOOTNOTE};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+const PLACEMENT_SECTION: &str = "section";
+
+pub(crate) trait ProcessFootnotes {
+    fn process_footnotes(&mut self);
+}
+
+#[derive(Clone, Debug)]
+pub struct Footnote {
+    pub key: String,
+    pub(crate) text: TextLine,
+    pub(crate) number: usize,
+    pub(crate) ref_count: usize,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct FootnoteReference {
+    pub(crate) key: String,
+    pub(crate) footnote: Option<Arc<RwLock<Footnote>>>,
+    pub(crate) index: usize,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Footnotes {
+    entries: HashMap<String, Arc<RwLock<Footnote>>>,
+    references: Vec<Arc<RwLock<FootnoteReference>>>,
+}
+
+impl Footnote {
+    pub fn new(key: String, text: TextLine) -> Self {
+        Self {
+            key,
+            text,
+            number: 0,
+            ref_count: 0,
+            span: Span::default(),
+        }
+    }
+}
+
+impl FootnoteReference {
+    pub fn new(key: String) -> Self {
+        Self {
+            key,
+            footnote: None,
+            index: 0,
+            span: Span::default(),
+        }
+    }
+}
+
+impl Footnotes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_footnote(&mut self, footnote: Arc<RwLock<Footnote>>) {
+        let key = footnote.read().unwrap().key.clone();
+        self.entries.insert(key, footnote);
+    }
+
+    pub fn add_reference(&mut self, reference: Arc<RwLock<FootnoteReference>>) {
+        self.references.push(reference)
+    }
+
+    pub fn get_footnote(&self, key: &str) -> Option<Arc<RwLock<Footnote>>> {
+        self.entries.get(key).cloned()
+    }
+
+    pub fn combine(&mut self, other: &mut Footnotes) {
+        let other_entries = std::mem::take(&mut other.entries);
+        self.entries.extend(other_entries);
+        self.references.append(&mut other.references);
+    }
+}
+
+impl ProcessFootnotes for Document {
+    /// numbers the footnotes in the order they are first referenced in
+    /// and assigns them to the end of the top level section or the document
+    fn process_footnotes(&mut self) {
+        let per_section = if let Some(entry) = self.config.get_entry(FOOTNOTE_PLACEMENT) {
+            entry.get().as_string().to_lowercase() == PLACEMENT_SECTION
+        } else {
+            false
+        };
+        let mut count = 0;
+        let mut document_footnotes = Vec::new();
+        let entries = &self.footnotes;
+
+        for block in &mut self.elements {
+            let mut footnotes = Vec::new();
+            block.for_each_inline(&mut |inline| {
+                if let Inline::Footnote(reference) = inline {
+                    let mut reference = reference.write().unwrap();
+                    if let Some(footnote) = entries.get_footnote(&reference.key) {
+                        {
+                            let mut footnote_raw = footnote.write().unwrap();
+                            if footnote_raw.number == 0 {
+                                count += 1;
+                                footnote_raw.number = count;
+                                footnotes.push(Arc::clone(&footnote));
+                            }
+                            footnote_raw.ref_count += 1;
+                            reference.index = footnote_raw.ref_count;
+                        }
+                        reference.footnote = Some(footnote);
+                    }
+                }
+            });
+            match block {
+                Block::Section(section) if per_section => section.footnotes = footnotes,
+                _ => document_footnotes.append(&mut footnotes),
+            }
+        }
+        self.footnote_list = document_footnotes;
+
+        for reference in &self.footnotes.references {
+            let reference = reference.read().unwrap();
+            if reference.footnote.is_none() {
+                let mut diagnostic = Diagnostic::warning(
+                    D_UNRESOLVED_FOOTNOTE,
+                    format!("unresolved footnote '{}'", reference.key),
+                    reference.span.clone(),
+                );
+                diagnostic.set_hint("footnotes are defined with [*key]: text");
+                self.diagnostics.add(diagnostic);
+            }
+        }
+    }
+}
@@ -0,0 +1,484 @@
+use crate::elements::*;
+use crate::utils::diagnostics::{Diagnostic, Diagnostics, D_INVALID_FORMULA};
+use std::collections::{HashMap, HashSet};
+
+pub(crate) trait ProcessFormulas {
+    fn process_formulas(&mut self);
+}
+
+/// The row number starting at 1 and the column index of a table cell
+type Position = (usize, usize);
+
+#[derive(Clone, Debug)]
+enum CellValue {
+    Number(f64),
+    Text,
+    Empty,
+    Formula(String),
+}
+
+/// Evaluates the formulas of a table.
+/// Cells are referenced like in a spreadsheet with the letter of their column
+/// and the number of their row e.g. B3 where the header rows are counted as well.
+struct FormulaEvaluator {
+    cells: HashMap<Position, CellValue>,
+    header_rows: usize,
+    row_count: usize,
+    results: HashMap<Position, Result<f64, String>>,
+    evaluating: HashSet<Position>,
+}
+
+/// A recursive descent parser that computes the value of a formula while parsing it
+struct FormulaParser<'a> {
+    chars: Vec<char>,
+    index: usize,
+    position: Position,
+    evaluator: &'a mut FormulaEvaluator,
+}
+
+impl ProcessFormulas for Document {
+    /// computes the values of all table cells with formulas
+    fn process_formulas(&mut self) {
+        let diagnostics = &mut self.diagnostics;
+        process_blocks(&mut self.elements, diagnostics);
+    }
+}
+
+fn process_blocks(blocks: &mut [Block], diagnostics: &mut Diagnostics) {
+    for block in blocks {
+        match block {
+            Block::Section(section) => process_blocks(&mut section.elements, diagnostics),
+            Block::List(list) => list
+                .items
+                .iter_mut()
+                .for_each(|i| process_list_item(i, diagnostics)),
+            Block::Quote(quote) => process_blocks(&mut quote.content, diagnostics),
+            Block::Admonition(admonition) => process_blocks(&mut admonition.content, diagnostics),
+            Block::Details(details) => process_blocks(&mut details.content, diagnostics),
+            Block::Tabs(tabs) => tabs
+                .panes
+                .iter_mut()
+                .for_each(|pane| process_blocks(&mut pane.content, diagnostics)),
+            Block::Columns(columns) => columns
+                .columns
+                .iter_mut()
+                .for_each(|column| process_blocks(column, diagnostics)),
+            Block::Aligned(aligned) => process_blocks(&mut aligned.content, diagnostics),
+            Block::Table(table) => process_table(table, diagnostics),
+            Block::Figure(figure) => {
+                if let FigureContent::Table(table) = &mut figure.content {
+                    process_table(table, diagnostics)
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn process_list_item(item: &mut ListItem, diagnostics: &mut Diagnostics) {
+    process_blocks(&mut item.content, diagnostics);
+    item.children
+        .iter_mut()
+        .for_each(|c| process_list_item(c, diagnostics));
+}
+
+fn process_table(table: &mut Table, diagnostics: &mut Diagnostics) {
+    table
+        .header
+        .iter_mut()
+        .chain(table.rows.iter_mut())
+        .flat_map(|r| r.cells.iter_mut())
+        .for_each(|c| process_blocks(&mut c.content, diagnostics));
+
+    let mut evaluator = FormulaEvaluator::new(table);
+    let decimals = table
+        .metadata
+        .as_ref()
+        .and_then(|m| m.get_decimals(&table.span, diagnostics));
+    let header_rows = table.header.len();
+    let rows = table.header.iter_mut().chain(table.rows.iter_mut());
+
+    for (index, row) in rows.enumerate() {
+        for cell in row.cells.iter_mut().filter(|c| c.formula.is_some()) {
+            let position = (index + 1, cell.column);
+            match evaluator.evaluate_cell(position) {
+                Ok(value) => {
+                    let mut text = TextLine::new();
+                    text.span = cell.span.clone();
+                    text.add_subtext(Inline::Plain(PlainText::new(format_number(
+                        value, decimals,
+                    ))));
+                    let mut paragraph = Paragraph::new();
+                    paragraph.span = cell.span.clone();
+                    paragraph.add_element(Line::Text(text));
+                    cell.content = vec![Block::Paragraph(paragraph)];
+                }
+                Err(message) => {
+                    let mut diagnostic = Diagnostic::error(
+                        D_INVALID_FORMULA,
+                        format!(
+                            "invalid formula in cell {}: {}",
+                            get_cell_name(position),
+                            message
+                        ),
+                        cell.span.clone(),
+                    );
+                    if index < header_rows {
+                        diagnostic.set_hint("the rows of the header are counted as well");
+                    } else {
+                        diagnostic.set_hint(
+                            "cells are referenced by their column letter and row number e.g. B2",
+                        );
+                    }
+                    diagnostics.add(diagnostic);
+                }
+            }
+        }
+    }
+}
+
+impl FormulaEvaluator {
+    fn new(table: &Table) -> Self {
+        let mut cells = HashMap::new();
+        let rows = table.header.iter().chain(table.rows.iter());
+        let mut row_count = 0;
+        for (index, row) in rows.enumerate() {
+            row_count = index + 1;
+            for cell in &row.cells {
+                cells.insert((index + 1, cell.column), get_cell_value(cell));
+            }
+        }
+
+        Self {
+            cells,
+            header_rows: table.header.len(),
+            row_count,
+            results: HashMap::new(),
+            evaluating: HashSet::new(),
+        }
+    }
+
+    /// returns the numeric value of a cell and evaluates its formula if it has one
+    fn evaluate_cell(&mut self, position: Position) -> Result<f64, String> {
+        if let Some(result) = self.results.get(&position) {
+            return result.clone();
+        }
+        let name = get_cell_name(position);
+        match self.cells.get(&position).cloned() {
+            Some(CellValue::Number(number)) => Ok(number),
+            Some(CellValue::Text) => Err(format!("cell {} is not a number", name)),
+            Some(CellValue::Empty) => Err(format!("cell {} is empty", name)),
+            None => Err(format!("cell {} doesn't exist", name)),
+            Some(CellValue::Formula(formula)) => {
+                if !self.evaluating.insert(position) {
+                    return Err(format!("circular reference to cell {}", name));
+                }
+                let result = FormulaParser::new(&formula, position, self).parse();
+                self.evaluating.remove(&position);
+                self.results.insert(position, result.clone());
+
+                result
+            }
+        }
+    }
+
+    /// returns the values of all non-empty cells in the rectangle between two cells
+    fn evaluate_range(&mut self, start: Position, end: Position) -> Result<Vec<f64>, String> {
+        let mut values = Vec::new();
+        for row in start.0.min(end.0)..=start.0.max(end.0) {
+            for column in start.1.min(end.1)..=start.1.max(end.1) {
+                match self.cells.get(&(row, column)) {
+                    None | Some(CellValue::Empty) => {}
+                    _ => values.push(self.evaluate_cell((row, column))?),
+                }
+            }
+        }
+
+        Ok(values)
+    }
+}
+
+impl<'a> FormulaParser<'a> {
+    fn new(formula: &str, position: Position, evaluator: &'a mut FormulaEvaluator) -> Self {
+        Self {
+            chars: formula.chars().collect(),
+            index: 0,
+            position,
+            evaluator,
+        }
+    }
+
+    fn parse(&mut self) -> Result<f64, String> {
+        let value = self.parse_expression()?;
+        self.skip_whitespace();
+        if let Some(ch) = self.current() {
+            Err(format!("unexpected '{}'", ch))
+        } else {
+            Ok(value)
+        }
+    }
+
+    fn current(&self) -> Option<char> {
+        self.chars.get(self.index).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.current().is_some_and(char::is_whitespace) {
+            self.index += 1;
+        }
+    }
+
+    /// skips the given char and returns if it was found
+    fn consume(&mut self, ch: char) -> bool {
+        self.skip_whitespace();
+        if self.current() == Some(ch) {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_expression(&mut self) -> Result<f64, String> {
+        let mut value = self.parse_term()?;
+        loop {
+            if self.consume('+') {
+                value += self.parse_term()?;
+            } else if self.consume('-') {
+                value -= self.parse_term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_term(&mut self) -> Result<f64, String> {
+        let mut value = self.parse_factor()?;
+        loop {
+            if self.consume('*') {
+                value *= self.parse_factor()?;
+            } else if self.consume('/') {
+                let divisor = self.parse_factor()?;
+                if divisor == 0.0 {
+                    return Err("division by zero".to_string());
+                }
+                value /= divisor;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn parse_factor(&mut self) -> Result<f64, String> {
+        self.skip_whitespace();
+        if self.consume('-') {
+            return Ok(-self.parse_factor()?);
+        }
+        if self.consume('(') {
+            let value = self.parse_expression()?;
+            if !self.consume(')') {
+                return Err("missing closing parenthesis".to_string());
+            }
+            return Ok(value);
+        }
+        match self.current() {
+            Some(ch) if ch.is_ascii_digit() || ch == '.' => self.parse_number(),
+            Some(ch) if ch.is_ascii_alphabetic() => {
+                let name = self.parse_letters();
+                if self.consume('(') {
+                    self.parse_function(&name)
+                } else if let Some(row) = self.parse_row_number() {
+                    let column = get_column_index(&name)?;
+                    self.evaluator.evaluate_cell((row, column))
+                } else {
+                    Err(format!("unknown name '{}'", name))
+                }
+            }
+            Some(ch) => Err(format!("unexpected '{}'", ch)),
+            None => Err("unexpected end of formula".to_string()),
+        }
+    }
+
+    fn parse_number(&mut self) -> Result<f64, String> {
+        let start = self.index;
+        while self
+            .current()
+            .is_some_and(|c| c.is_ascii_digit() || c == '.')
+        {
+            self.index += 1;
+        }
+        let number: String = self.chars[start..self.index].iter().collect();
+
+        number
+            .parse::<f64>()
+            .map_err(|_| format!("invalid number '{}'", number))
+    }
+
+    fn parse_letters(&mut self) -> String {
+        let start = self.index;
+        while self.current().is_some_and(|c| c.is_ascii_alphabetic()) {
+            self.index += 1;
+        }
+
+        self.chars[start..self.index].iter().collect()
+    }
+
+    fn parse_row_number(&mut self) -> Option<usize> {
+        let start = self.index;
+        while self.current().is_some_and(|c| c.is_ascii_digit()) {
+            self.index += 1;
+        }
+        let number: String = self.chars[start..self.index].iter().collect();
+
+        number.parse::<usize>().ok().filter(|n| *n > 0)
+    }
+
+    /// parses the arguments of a function and applies the function to them
+    fn parse_function(&mut self, name: &str) -> Result<f64, String> {
+        let mut values = Vec::new();
+        if !self.consume(')') {
+            loop {
+                self.parse_argument(&mut values)?;
+                if self.consume(')') {
+                    break;
+                }
+                if !self.consume(',') {
+                    return Err(format!("missing closing parenthesis of {}", name));
+                }
+            }
+        }
+
+        match name.to_lowercase().as_str() {
+            "sum" => Ok(values.iter().sum()),
+            "avg" | "average" if values.is_empty() => {
+                Err(format!("{} of no values", name.to_lowercase()))
+            }
+            "avg" | "average" => Ok(values.iter().sum::<f64>() / values.len() as f64),
+            "min" => values
+                .into_iter()
+                .reduce(f64::min)
+                .ok_or_else(|| "min of no values".to_string()),
+            "max" => values
+                .into_iter()
+                .reduce(f64::max)
+                .ok_or_else(|| "max of no values".to_string()),
+            "count" => Ok(values.len() as f64),
+            _ => Err(format!("unknown function '{}'", name)),
+        }
+    }
+
+    /// parses a range like B2:B9, a column like B or an expression
+    fn parse_argument(&mut self, values: &mut Vec<f64>) -> Result<(), String> {
+        self.skip_whitespace();
+        let start = self.index;
+        let column = self.parse_letters();
+        if !column.is_empty() {
+            let start_column = get_column_index(&column)?;
+            let start_row = self.parse_row_number();
+            if self.consume(':') {
+                self.skip_whitespace();
+                let end_column = get_column_index(&self.parse_letters())?;
+                let end_row = self.parse_row_number();
+                let (first_row, last_row) = match (start_row, end_row) {
+                    (Some(start_row), Some(end_row)) => (start_row, end_row),
+                    (None, None) => (self.evaluator.header_rows + 1, self.evaluator.row_count),
+                    _ => return Err("invalid range".to_string()),
+                };
+                values.append(
+                    &mut self
+                        .evaluator
+                        .evaluate_range((first_row, start_column), (last_row, end_column))?,
+                );
+                return Ok(());
+            }
+            self.skip_whitespace();
+            if start_row.is_none() && matches!(self.current(), Some(',') | Some(')')) {
+                // a single column stands for all of its cells below the header
+                // except the one containing the formula
+                for row in self.evaluator.header_rows + 1..=self.evaluator.row_count {
+                    if (row, start_column) != self.position {
+                        values.append(
+                            &mut self
+                                .evaluator
+                                .evaluate_range((row, start_column), (row, start_column))?,
+                        );
+                    }
+                }
+                return Ok(());
+            }
+        }
+        self.index = start;
+        values.push(self.parse_expression()?);
+
+        Ok(())
+    }
+}
+
+/// returns the value of a cell that only contains plain text
+fn get_cell_value(cell: &Cell) -> CellValue {
+    if let Some(formula) = &cell.formula {
+        return CellValue::Formula(formula.clone());
+    }
+    let mut text = String::new();
+    for block in &cell.content {
+        if let Block::Paragraph(paragraph) = block {
+            for line in &paragraph.elements {
+                if let Line::Text(line) = line {
+                    for inline in &line.subtext {
+                        if let Inline::Plain(plain) = inline {
+                            text.push_str(&plain.value);
+                        } else {
+                            return CellValue::Text;
+                        }
+                    }
+                }
+            }
+        } else {
+            return CellValue::Text;
+        }
+    }
+    let text = text.trim();
+    if text.is_empty() {
+        CellValue::Empty
+    } else if let Ok(number) = text.parse::<f64>() {
+        CellValue::Number(number)
+    } else {
+        CellValue::Text
+    }
+}
+
+/// returns the index of a column from its letters where A is 0 and AA is 26
+fn get_column_index(letters: &str) -> Result<usize, String> {
+    letters
+        .to_ascii_uppercase()
+        .chars()
+        .try_fold(0usize, |index, c| {
+            index
+                .checked_mul(26)?
+                .checked_add(c as usize - 'A' as usize + 1)
+        })
+        .map(|index| index.saturating_sub(1))
+        .ok_or_else(|| format!("invalid cell reference '{}'", letters))
+}
+
+/// returns the spreadsheet name of a cell e.g. B3
+fn get_cell_name(position: Position) -> String {
+    let mut letters = String::new();
+    let mut column = position.1 + 1;
+    while column > 0 {
+        letters.insert(0, (b'A' + ((column - 1) % 26) as u8) as char);
+        column = (column - 1) / 26;
+    }
+
+    format!("{}{}", letters, position.0)
+}
+
+/// formats the result of a formula with the given number of decimals
+/// or without trailing zeros
+fn format_number(value: f64, decimals: Option<usize>) -> String {
+    if let Some(decimals) = decimals {
+        format!("{:.*}", decimals, value)
+    } else {
+        format!("{}", (value * 1e10).round() / 1e10)
+    }
+}
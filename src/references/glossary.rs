@@ -0,0 +1,166 @@
+use crate::elements::*;
+use crate::utils::diagnostics::{Diagnostic, D_UNRESOLVED_TERM};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+const G_TERM: &str = "term";
+const G_LONG: &str = "long";
+const G_DESCRIPTION: &str = "description";
+
+pub(crate) trait ProcessGlossary {
+    fn process_glossary(&mut self);
+}
+
+#[derive(Clone, Debug)]
+pub struct GlossaryEntry {
+    pub key: String,
+    pub term: String,
+    pub long: Option<String>,
+    pub description: Option<String>,
+    pub(crate) ref_count: usize,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug)]
+pub struct GlossaryReference {
+    pub(crate) key: String,
+    pub(crate) entry: Option<Arc<RwLock<GlossaryEntry>>>,
+    pub(crate) index: usize,
+    pub(crate) span: Span,
+}
+
+/// The list of referenced glossary entries a [[glossary]] placeholder is replaced with
+#[derive(Clone, Debug)]
+pub struct GlossaryList {
+    pub(crate) entries: Vec<Arc<RwLock<GlossaryEntry>>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Glossary {
+    entries: HashMap<String, Arc<RwLock<GlossaryEntry>>>,
+    references: Vec<Arc<RwLock<GlossaryReference>>>,
+}
+
+impl GlossaryEntry {
+    pub fn from_metadata(key: String, data: Box<dyn Metadata>) -> Self {
+        Self {
+            term: data.get_string(G_TERM).unwrap_or_else(|| key.clone()),
+            key,
+            long: data.get_string(G_LONG),
+            description: data.get_string(G_DESCRIPTION),
+            ref_count: 0,
+            span: Span::default(),
+        }
+    }
+}
+
+impl GlossaryReference {
+    pub fn new(key: String) -> Self {
+        Self {
+            key,
+            entry: None,
+            index: 0,
+            span: Span::default(),
+        }
+    }
+
+    /// returns the text of the reference
+    /// The first use of an acronym is expanded to "Long Form (TERM)"
+    pub(crate) fn get_formatted(&self) -> String {
+        if let Some(entry) = &self.entry {
+            let entry = entry.read().unwrap();
+            match &entry.long {
+                Some(long) if self.index == 1 => format!("{} ({})", long, entry.term),
+                _ => entry.term.clone(),
+            }
+        } else {
+            self.key.clone()
+        }
+    }
+}
+
+impl Glossary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_entry(&mut self, entry: Arc<RwLock<GlossaryEntry>>) {
+        let key = entry.read().unwrap().key.clone();
+        self.entries.insert(key, entry);
+    }
+
+    pub fn add_reference(&mut self, reference: Arc<RwLock<GlossaryReference>>) {
+        self.references.push(reference)
+    }
+
+    /// assigns the entries to the references
+    pub(crate) fn assign_entries(&mut self) {
+        let entries = &self.entries;
+        self.references.iter().for_each(|r| {
+            let mut reference = r.write().unwrap();
+            reference.entry = entries.get(&reference.key).cloned();
+        })
+    }
+
+    /// returns all references that couldn't be assigned to an entry
+    pub(crate) fn get_unresolved_references(&self) -> Vec<Arc<RwLock<GlossaryReference>>> {
+        self.references
+            .iter()
+            .filter(|r| r.read().unwrap().entry.is_none())
+            .cloned()
+            .collect()
+    }
+
+    /// creates the alphabetically sorted list of all referenced entries
+    pub fn create_list(&self) -> GlossaryList {
+        let mut entries: Vec<Arc<RwLock<GlossaryEntry>>> = self
+            .entries
+            .values()
+            .filter(|e| e.read().unwrap().ref_count > 0)
+            .cloned()
+            .collect();
+        entries.sort_by_cached_key(|e| e.read().unwrap().term.to_lowercase());
+
+        GlossaryList { entries }
+    }
+
+    pub fn combine(&mut self, other: &mut Glossary) {
+        let other_entries = std::mem::take(&mut other.entries);
+        self.entries.extend(other_entries);
+        self.references.append(&mut other.references);
+    }
+}
+
+impl ProcessGlossary for Document {
+    /// resolves the glossary references and counts the uses of each entry
+    /// in the order they appear in the document
+    fn process_glossary(&mut self) {
+        self.glossary.assign_entries();
+        for block in &self.elements {
+            block.for_each_inline(&mut |inline| {
+                if let Inline::GlossaryReference(reference) = inline {
+                    let mut reference = reference.write().unwrap();
+                    if let Some(entry) = reference.entry.clone() {
+                        let mut entry = entry.write().unwrap();
+                        entry.ref_count += 1;
+                        reference.index = entry.ref_count;
+                    }
+                }
+            });
+        }
+
+        for reference in self.glossary.get_unresolved_references() {
+            let reference = reference.read().unwrap();
+            let mut diagnostic = Diagnostic::warning(
+                D_UNRESOLVED_TERM,
+                format!("unresolved glossary term '{}'", reference.key),
+                reference.span.clone(),
+            );
+            diagnostic.set_hint(&format!(
+                "define the term with `[~{}]:[long=\"..\" description=\"..\"]`",
+                reference.key
+            ));
+            self.diagnostics.add(diagnostic);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use crate::elements::Span;
+use std::collections::BTreeMap;
+
+/// An invisible marker of a keyword that is listed in the index
+#[derive(Clone, Debug)]
+pub struct IndexMarker {
+    /// the term followed by its sub entries e.g. parser!errors
+    pub(crate) path: Vec<String>,
+    pub(crate) number: usize,
+    pub(crate) span: Span,
+}
+
+/// The index a [[index]] placeholder is replaced with
+#[derive(Clone, Debug, Default)]
+pub struct IndexList {
+    pub(crate) groups: Vec<IndexGroup>,
+}
+
+/// All entries of the index starting with the same letter
+#[derive(Clone, Debug)]
+pub struct IndexGroup {
+    pub(crate) letter: char,
+    pub(crate) entries: Vec<IndexEntry>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct IndexEntry {
+    pub(crate) term: String,
+    /// the anchors of all markers of the entry
+    pub(crate) anchors: Vec<String>,
+    pub(crate) children: Vec<IndexEntry>,
+}
+
+/// Collects the markers of a document into a sorted tree of entries
+#[derive(Default)]
+pub(crate) struct IndexBuilder {
+    root: BuilderNode,
+}
+
+#[derive(Default)]
+struct BuilderNode {
+    term: String,
+    anchors: Vec<String>,
+    children: BTreeMap<String, BuilderNode>,
+}
+
+impl IndexMarker {
+    pub fn new(path: Vec<String>) -> Self {
+        Self {
+            path,
+            number: 0,
+            span: Span::default(),
+        }
+    }
+
+    pub(crate) fn get_anchor(&self) -> String {
+        format!("index-{}", self.number)
+    }
+}
+
+impl IndexBuilder {
+    pub(crate) fn add_marker(&mut self, marker: &IndexMarker) {
+        let mut node = &mut self.root;
+        for term in &marker.path {
+            node = node
+                .children
+                .entry(term.to_lowercase())
+                .or_insert_with(|| BuilderNode {
+                    term: term.clone(),
+                    ..Default::default()
+                });
+        }
+        node.anchors.push(marker.get_anchor());
+    }
+
+    /// returns the entries grouped by the uppercase first letter of their term
+    pub(crate) fn build(self) -> IndexList {
+        let mut list = IndexList::default();
+        for node in self.root.children.into_values() {
+            let letter = node
+                .term
+                .chars()
+                .next()
+                .filter(|c| c.is_alphabetic())
+                .map(|c| c.to_uppercase().next().unwrap_or(c))
+                .unwrap_or('#');
+            let entry = node.into_entry();
+            if let Some(group) = list.groups.iter_mut().find(|g| g.letter == letter) {
+                group.entries.push(entry)
+            } else {
+                list.groups.push(IndexGroup {
+                    letter,
+                    entries: vec![entry],
+                })
+            }
+        }
+
+        list
+    }
+}
+
+impl BuilderNode {
+    fn into_entry(self) -> IndexEntry {
+        IndexEntry {
+            term: self.term,
+            anchors: self.anchors,
+            children: self
+                .children
+                .into_values()
+                .map(|c| c.into_entry())
+                .collect(),
+        }
+    }
+}
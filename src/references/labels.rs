@@ -0,0 +1,359 @@
+use crate::elements::*;
+use crate::format::PlaceholderTemplate;
+use crate::references::configuration::keys::{
+    FIGURE_DISPLAY, LISTING_DISPLAY, QUOTE_DISPLAY, SECTION_DISPLAY, TABLE_DISPLAY,
+};
+use crate::references::configuration::ConfigRefEntry;
+use crate::utils::diagnostics::{Diagnostic, D_DUPLICATE_LABEL, D_UNRESOLVED_LABEL};
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, RwLock};
+
+const M_LABEL: &str = "label";
+
+pub(crate) trait ProcessLabels {
+    fn process_labels(&mut self);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LabelKind {
+    Section,
+    Figure,
+    Table,
+    Listing,
+    Quote,
+}
+
+#[derive(Clone, Debug)]
+pub struct LabelEntry {
+    pub key: String,
+    pub kind: LabelKind,
+    pub number: String,
+    pub(crate) span: Span,
+    pub(crate) display: Option<ConfigRefEntry>,
+}
+
+#[derive(Clone, Debug)]
+pub struct LabelReference {
+    pub(crate) key: String,
+    pub(crate) entry: Option<LabelEntry>,
+    pub(crate) span: Span,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Labels {
+    entries: HashMap<String, LabelEntry>,
+    references: Vec<Arc<RwLock<LabelReference>>>,
+}
+
+/// Counts the numbered elements of a document in the order they appear in
+#[derive(Default)]
+struct LabelCounter {
+    sections: Vec<usize>,
+    figures: usize,
+    tables: usize,
+    listings: usize,
+    quotes: usize,
+    entries: Vec<LabelEntry>,
+}
+
+impl LabelKind {
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            LabelKind::Section => "Section",
+            LabelKind::Figure => "Figure",
+            LabelKind::Table => "Table",
+            LabelKind::Listing => "Listing",
+            LabelKind::Quote => "Quote",
+        }
+    }
+
+    /// returns the config key of the display template for the numbers of the kind
+    pub fn get_config_key(&self) -> &'static str {
+        match self {
+            LabelKind::Section => SECTION_DISPLAY,
+            LabelKind::Figure => FIGURE_DISPLAY,
+            LabelKind::Table => TABLE_DISPLAY,
+            LabelKind::Listing => LISTING_DISPLAY,
+            LabelKind::Quote => QUOTE_DISPLAY,
+        }
+    }
+
+    /// formats a number with the given display template e.g. "Figure {{number}}"
+    pub(crate) fn format_number(&self, display: &Option<ConfigRefEntry>, number: &str) -> String {
+        if let Some(display) = display {
+            let mut template = PlaceholderTemplate::new(display.read().unwrap().get().as_string());
+            template.add_replacement("number", number);
+            template.render()
+        } else {
+            format!("{} {}", self.get_name(), number)
+        }
+    }
+}
+
+impl LabelReference {
+    pub fn new(key: String) -> Self {
+        Self {
+            key,
+            entry: None,
+            span: Span::default(),
+        }
+    }
+
+    /// returns the text the reference is displayed with e.g. "Figure 3"
+    pub(crate) fn get_formatted(&self) -> String {
+        if let Some(entry) = &self.entry {
+            entry
+                .kind
+                .format_number(&entry.display, entry.number.as_str())
+        } else {
+            "??".to_string()
+        }
+    }
+}
+
+impl Labels {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+            references: Vec::new(),
+        }
+    }
+
+    pub fn add_reference(&mut self, reference: Arc<RwLock<LabelReference>>) {
+        self.references.push(reference)
+    }
+
+    /// adds a labelled element and returns the previous one with the same key
+    pub(crate) fn add_entry(&mut self, entry: LabelEntry) -> Option<LabelEntry> {
+        self.entries.insert(entry.key.clone(), entry)
+    }
+
+    pub fn get_entry(&self, key: &str) -> Option<&LabelEntry> {
+        self.entries.get(key)
+    }
+
+    /// assigns the labelled elements to the references
+    pub(crate) fn assign_references(&mut self) {
+        let entries = &self.entries;
+        self.references.iter().for_each(|r| {
+            let mut reference = r.write().unwrap();
+            reference.entry = entries.get(&reference.key).cloned();
+        })
+    }
+
+    /// returns all references that point to a label that doesn't exist
+    pub(crate) fn get_unresolved_references(&self) -> Vec<Arc<RwLock<LabelReference>>> {
+        self.references
+            .iter()
+            .filter(|r| r.read().unwrap().entry.is_none())
+            .cloned()
+            .collect()
+    }
+
+    pub fn combine(&mut self, other: &mut Labels) {
+        let other_entries = std::mem::take(&mut other.entries);
+        self.entries.extend(other_entries);
+        self.references.append(&mut other.references);
+    }
+}
+
+impl LabelCounter {
+    /// increments the counter of the kind and returns the new number
+    fn next(&mut self, kind: LabelKind) -> usize {
+        let counter = match kind {
+            LabelKind::Figure => &mut self.figures,
+            LabelKind::Table => &mut self.tables,
+            LabelKind::Listing => &mut self.listings,
+            LabelKind::Quote => &mut self.quotes,
+            LabelKind::Section => self.sections.last_mut().unwrap(),
+        };
+        *counter += 1;
+
+        *counter
+    }
+
+    fn add(&mut self, key: String, kind: LabelKind, number: String, span: Span) {
+        self.entries.push(LabelEntry {
+            key,
+            kind,
+            number,
+            span,
+            display: None,
+        })
+    }
+
+    /// numbers an element that isn't part of a figure if it has a label
+    fn add_labelled(&mut self, metadata: &Option<InlineMetadata>, kind: LabelKind, span: &Span) {
+        if let Some(key) = metadata.as_ref().and_then(|m| m.get_string(M_LABEL)) {
+            let number = self.next(kind);
+            self.add(key, kind, number.to_string(), span.clone())
+        }
+    }
+
+    fn count_blocks(&mut self, blocks: &mut [Block]) {
+        blocks.iter_mut().for_each(|b| self.count_block(b))
+    }
+
+    fn count_block(&mut self, block: &mut Block) {
+        match block {
+            Block::Section(section) => {
+                let depth = self.sections.len();
+                self.next(LabelKind::Section);
+                let number = self
+                    .sections
+                    .iter()
+                    .map(|n| n.to_string())
+                    .collect::<Vec<String>>()
+                    .join(".");
+                if let Some(key) = section
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.get_string(M_LABEL))
+                {
+                    self.add(key, LabelKind::Section, number, section.span.clone());
+                }
+                self.sections.push(0);
+                self.count_blocks(&mut section.elements);
+                self.sections.truncate(depth);
+            }
+            Block::Figure(figure) => {
+                let kind = figure.get_kind();
+                figure.number = self.next(kind);
+                if let Some(key) = figure.get_label() {
+                    self.add(key, kind, figure.number.to_string(), figure.span.clone());
+                }
+                if let FigureContent::Table(table) = &mut figure.content {
+                    self.count_table(table);
+                }
+                self.count_inlines(&figure.caption.subtext);
+            }
+            Block::Paragraph(paragraph) => {
+                paragraph.elements.iter().for_each(|l| self.count_line(l))
+            }
+            Block::List(list) => list.items.iter_mut().for_each(|i| self.count_list_item(i)),
+            Block::Table(table) => {
+                self.add_labelled(&table.metadata, LabelKind::Table, &table.span);
+                self.count_table(table);
+            }
+            Block::CodeBlock(code) => {
+                self.add_labelled(&code.metadata, LabelKind::Listing, &code.span);
+            }
+            Block::Quote(quote) => {
+                self.add_labelled(&quote.metadata, LabelKind::Quote, &quote.span);
+                self.count_blocks(&mut quote.content);
+            }
+            Block::Admonition(admonition) => {
+                if let Some(title) = &admonition.title {
+                    self.count_inlines(&title.subtext);
+                }
+                self.count_blocks(&mut admonition.content);
+            }
+            Block::Details(details) => {
+                if let Some(summary) = &details.summary {
+                    self.count_inlines(&summary.subtext);
+                }
+                self.count_blocks(&mut details.content);
+            }
+            Block::Tabs(tabs) => tabs.panes.iter_mut().for_each(|pane| {
+                if let Some(title) = &pane.title {
+                    self.count_inlines(&title.subtext);
+                }
+                self.count_blocks(&mut pane.content);
+            }),
+            Block::Columns(columns) => columns
+                .columns
+                .iter_mut()
+                .for_each(|column| self.count_blocks(column)),
+            Block::Aligned(aligned) => self.count_blocks(&mut aligned.content),
+            Block::Import(import) => {
+                let mut anchor = import.anchor.write().unwrap();
+                if let Some(document) = &mut anchor.document {
+                    self.count_blocks(&mut document.elements);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn count_table(&mut self, table: &mut Table) {
+        table
+            .header
+            .iter_mut()
+            .chain(table.rows.iter_mut())
+            .for_each(|row| {
+                row.cells
+                    .iter_mut()
+                    .for_each(|c| self.count_blocks(&mut c.content))
+            })
+    }
+
+    fn count_list_item(&mut self, item: &mut ListItem) {
+        self.count_blocks(&mut item.content);
+        item.children
+            .iter_mut()
+            .for_each(|c| self.count_list_item(c));
+    }
+
+    fn count_line(&mut self, line: &Line) {
+        if let Line::Text(text) = line {
+            self.count_inlines(&text.subtext)
+        }
+    }
+
+    fn count_inlines(&mut self, inlines: &[Inline]) {
+        inlines.iter().for_each(|inline| {
+            if let Inline::Image(image) = inline {
+                if let Some(key) = image.metadata.as_ref().and_then(|m| m.get_string(M_LABEL)) {
+                    let number = self.next(LabelKind::Figure);
+                    image.number.store(number, Ordering::Relaxed);
+                    self.add(
+                        key,
+                        LabelKind::Figure,
+                        number.to_string(),
+                        image.span.clone(),
+                    );
+                }
+            }
+        })
+    }
+}
+
+impl ProcessLabels for Document {
+    /// numbers all labelled elements and resolves the references to them
+    fn process_labels(&mut self) {
+        let mut counter = LabelCounter {
+            sections: vec![0],
+            ..Default::default()
+        };
+        counter.count_blocks(&mut self.elements);
+
+        for mut entry in counter.entries {
+            entry.display = self.config.get_ref_entry(entry.kind.get_config_key());
+            let span = entry.span.clone();
+            if let Some(previous) = self.labels.add_entry(entry) {
+                let mut diagnostic = Diagnostic::warning(
+                    D_DUPLICATE_LABEL,
+                    format!("duplicate label '{}'", previous.key),
+                    span,
+                );
+                diagnostic.set_hint("labels must be unique across all imported documents");
+                self.diagnostics.add(diagnostic);
+            }
+        }
+        self.labels.assign_references();
+
+        for reference in self.labels.get_unresolved_references() {
+            let reference = reference.read().unwrap();
+            let mut diagnostic = Diagnostic::warning(
+                D_UNRESOLVED_LABEL,
+                format!("reference to unknown label '{}'", reference.key),
+                reference.span.clone(),
+            );
+            diagnostic.set_hint(
+                "labels are assigned with [label=name] on sections, images, tables, code blocks and quotes",
+            );
+            self.diagnostics.add(diagnostic);
+        }
+    }
+}
@@ -1,4 +1,9 @@
 pub mod bibliography;
 pub mod configuration;
+pub mod footnotes;
+pub mod formulas;
+pub mod glossary;
+pub mod index;
+pub mod labels;
 pub mod placeholders;
 pub mod templates;
@@ -1,4 +1,6 @@
 use crate::elements::*;
+use crate::references::labels::LabelKind;
+use crate::utils::diagnostics::{Diagnostic, D_UNKNOWN_PLACEHOLDER};
 use chrono::prelude::*;
 use regex::Regex;
 
@@ -29,6 +31,10 @@ pub(crate) trait ProcessPlaceholders {
 const S_VALUE: &str = "value";
 
 const P_TOC: &str = "toc";
+const P_LOF: &str = "lof";
+const P_LOT: &str = "lot";
+const P_GLOSSARY: &str = "glossary";
+const P_INDEX: &str = "index";
 const P_DATE: &str = "date";
 const P_TIME: &str = "time";
 const P_DATETIME: &str = "datetime";
@@ -36,6 +42,7 @@ const P_DATETIME: &str = "datetime";
 impl ProcessPlaceholders for Document {
     /// parses all placeholders and assigns values to them
     fn process_placeholders(&mut self) {
+        let mut unknown = Vec::new();
         self.placeholders.iter().for_each(|p| {
             let mut pholder = p.write().unwrap();
             match pholder.name.to_lowercase().as_str() {
@@ -47,24 +54,52 @@ impl ProcessPlaceholders for Document {
                     };
                     pholder.set_value(block!(Block::List(self.create_toc(ordered))))
                 }
-                P_DATE => pholder.set_value(inline!(Inline::Plain(PlainText {
-                    value: get_date_string()
-                }))),
-                P_TIME => pholder.set_value(inline!(Inline::Plain(PlainText {
-                    value: get_time_string()
-                }))),
-                P_DATETIME => pholder.set_value(inline!(Inline::Plain(PlainText {
-                    value: format!("{} {}", get_date_string(), get_time_string())
-                }))),
+                P_LOF | P_LOT => {
+                    let ordered = if let Some(meta) = &pholder.metadata {
+                        meta.get_bool("ordered")
+                    } else {
+                        false
+                    };
+                    let kind = if pholder.name.to_lowercase() == P_LOF {
+                        LabelKind::Figure
+                    } else {
+                        LabelKind::Table
+                    };
+                    pholder.set_value(block!(Block::List(self.create_figure_list(kind, ordered))))
+                }
+                P_GLOSSARY => {
+                    pholder.set_value(block!(Block::GlossaryList(self.glossary.create_list())))
+                }
+                P_INDEX => pholder.set_value(block!(Block::Index(self.create_index()))),
+                P_DATE => {
+                    pholder.set_value(inline!(Inline::Plain(PlainText::new(get_date_string()))))
+                }
+                P_TIME => {
+                    pholder.set_value(inline!(Inline::Plain(PlainText::new(get_time_string()))))
+                }
+                P_DATETIME => pholder.set_value(inline!(Inline::Plain(PlainText::new(format!(
+                    "{} {}",
+                    get_date_string(),
+                    get_time_string()
+                ))))),
                 _ => {
                     if let Some(entry) = self.config.get_entry(pholder.name.to_lowercase().as_str())
                     {
                         let value = entry.get().as_string();
-                        pholder.set_value(inline!(Inline::Plain(PlainText { value })))
+                        pholder.set_value(inline!(Inline::Plain(PlainText::new(value))))
+                    } else if pholder.value.is_none() {
+                        let mut diagnostic = Diagnostic::warning(
+                            D_UNKNOWN_PLACEHOLDER,
+                            format!("unknown placeholder '{}'", pholder.name),
+                            pholder.span.clone(),
+                        );
+                        diagnostic.set_hint("placeholders are either builtin or config values");
+                        unknown.push(diagnostic);
                     }
                 }
             }
-        })
+        });
+        unknown.into_iter().for_each(|d| self.diagnostics.add(d));
     }
 
     fn process_definitions(&mut self) {
@@ -80,9 +115,7 @@ impl ProcessPlaceholders for Document {
             if let Some(cap) = RE_SET.captures(&name) {
                 if let Some(key) = cap.get(1) {
                     let key: &str = key.as_str();
-                    pholder.value = Some(inline!(Inline::Plain(PlainText {
-                        value: "".to_string()
-                    })));
+                    pholder.value = Some(inline!(Inline::Plain(PlainText::new("".to_string()))));
                     if let Some(meta) = &pholder.metadata {
                         if let Some(value) = meta.data.get(S_VALUE) {
                             self.config.set_from_meta(key, value.clone())
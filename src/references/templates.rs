@@ -1,4 +1,4 @@
-use crate::elements::{Block, Element, Inline, Line, ListItem};
+use crate::elements::{Block, Element, Inline, Line, ListItem, Span};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
@@ -22,6 +22,7 @@ pub struct TemplateVariable {
     pub(crate) name: String,
     pub(crate) suffix: String,
     pub(crate) value: Option<Element>,
+    pub(crate) span: Span,
 }
 
 impl Template {
@@ -84,13 +85,6 @@ impl GetTemplateVariables for Line {
                 .map(|s| s.get_template_variables())
                 .flatten()
                 .collect(),
-            Line::Centered(center) => center
-                .line
-                .subtext
-                .iter()
-                .map(|s| s.get_template_variables())
-                .flatten()
-                .collect(),
             _ => Vec::new(),
         }
     }
@@ -112,15 +106,36 @@ impl GetTemplateVariables for Block {
                 .flatten()
                 .collect(),
             Block::Quote(q) => q
-                .text
+                .content
+                .iter()
+                .flat_map(|b| b.get_template_variables())
+                .collect(),
+            Block::Admonition(a) => a
+                .content
+                .iter()
+                .flat_map(|b| b.get_template_variables())
+                .collect(),
+            Block::Details(d) => d
+                .content
+                .iter()
+                .flat_map(|b| b.get_template_variables())
+                .collect(),
+            Block::Tabs(t) => t
+                .panes
+                .iter()
+                .flat_map(|p| p.content.iter())
+                .flat_map(|b| b.get_template_variables())
+                .collect(),
+            Block::Columns(c) => c
+                .columns
                 .iter()
-                .map(|t| {
-                    t.subtext
-                        .iter()
-                        .map(|i| i.get_template_variables())
-                        .flatten()
-                })
                 .flatten()
+                .flat_map(|b| b.get_template_variables())
+                .collect(),
+            Block::Aligned(a) => a
+                .content
+                .iter()
+                .flat_map(|b| b.get_template_variables())
                 .collect(),
             Block::List(list) => list
                 .items
@@ -194,7 +209,7 @@ impl GetTemplateVariables for ListItem {
             .map(|child| child.get_template_variables())
             .flatten()
             .collect();
-        inner_vars.append(&mut self.text.get_template_variables());
+        inner_vars.extend(self.content.iter().flat_map(|b| b.get_template_variables()));
 
         inner_vars
     }
@@ -202,35 +217,18 @@ impl GetTemplateVariables for ListItem {
 
 impl FreezeVariables for Line {
     fn freeze_variables(&mut self) -> Option<Arc<RwLock<TemplateVariable>>> {
-        match self {
-            Line::Text(text) => {
-                text.subtext = text
-                    .subtext
-                    .iter_mut()
-                    .map(|i| {
-                        if let Some(t) = i.freeze_variables() {
-                            Inline::TemplateVar(t)
-                        } else {
-                            (*i).clone()
-                        }
-                    })
-                    .collect()
-            }
-            Line::Centered(center) => {
-                center.line.subtext = center
-                    .line
-                    .subtext
-                    .iter_mut()
-                    .map(|i| {
-                        if let Some(t) = i.freeze_variables() {
-                            Inline::TemplateVar(t)
-                        } else {
-                            (*i).clone()
-                        }
-                    })
-                    .collect()
-            }
-            _ => {}
+        if let Line::Text(text) = self {
+            text.subtext = text
+                .subtext
+                .iter_mut()
+                .map(|i| {
+                    if let Some(t) = i.freeze_variables() {
+                        Inline::TemplateVar(t)
+                    } else {
+                        (*i).clone()
+                    }
+                })
+                .collect()
         }
         None
     }
@@ -245,18 +243,27 @@ impl FreezeVariables for Block {
             Block::Paragraph(p) => p.elements.iter_mut().for_each(|l| {
                 l.freeze_variables();
             }),
-            Block::Quote(q) => q.text.iter_mut().for_each(|t| {
-                t.subtext = t
-                    .subtext
-                    .iter_mut()
-                    .map(|i| {
-                        if let Some(t) = i.freeze_variables() {
-                            Inline::TemplateVar(t)
-                        } else {
-                            (*i).clone()
-                        }
-                    })
-                    .collect()
+            Block::Quote(q) => q.content.iter_mut().for_each(|b| {
+                b.freeze_variables();
+            }),
+            Block::Admonition(a) => a.content.iter_mut().for_each(|b| {
+                b.freeze_variables();
+            }),
+            Block::Details(d) => d.content.iter_mut().for_each(|b| {
+                b.freeze_variables();
+            }),
+            Block::Tabs(t) => t
+                .panes
+                .iter_mut()
+                .flat_map(|p| p.content.iter_mut())
+                .for_each(|b| {
+                    b.freeze_variables();
+                }),
+            Block::Columns(c) => c.columns.iter_mut().flatten().for_each(|b| {
+                b.freeze_variables();
+            }),
+            Block::Aligned(a) => a.content.iter_mut().for_each(|b| {
+                b.freeze_variables();
             }),
             Block::List(list) => list.items.iter_mut().for_each(|item| {
                 item.freeze_variables();
@@ -285,7 +292,9 @@ impl FreezeVariables for ListItem {
         self.children.iter_mut().for_each(|child| {
             child.freeze_variables();
         });
-        self.text.freeze_variables();
+        self.content.iter_mut().for_each(|block| {
+            block.freeze_variables();
+        });
         None
     }
 }
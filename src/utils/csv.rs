@@ -0,0 +1,39 @@
+/// Reads the records of delimiter separated values like csv or tsv
+/// Fields can be quoted with double quotes to contain delimiters or line breaks.
+/// A double quote inside a quoted field is escaped with a second double quote.
+pub fn read_records(text: &str, delimiter: char) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        if quoted {
+            if ch != '"' {
+                field.push(ch);
+            } else if chars.peek() == Some(&'"') {
+                field.push('"');
+                chars.next();
+            } else {
+                quoted = false;
+            }
+        } else if ch == '"' && field.is_empty() {
+            quoted = true;
+        } else if ch == delimiter {
+            record.push(std::mem::take(&mut field));
+        } else if ch == '\n' {
+            record.push(std::mem::take(&mut field));
+            records.push(std::mem::take(&mut record));
+        } else if ch != '\r' {
+            field.push(ch);
+        }
+    }
+    if !field.is_empty() || !record.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records.retain(|r| r.len() > 1 || r.iter().any(|f| !f.trim().is_empty()));
+
+    records
+}
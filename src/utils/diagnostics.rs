@@ -0,0 +1,199 @@
+use crate::elements::Span;
+use colored::*;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+pub const D_PARSE_ERROR: &str = "parse-error";
+pub const D_IMPORT_MISSING: &str = "import-missing";
+pub const D_IMPORT_CYCLIC: &str = "import-cyclic";
+pub const D_UNRESOLVED_BIBREF: &str = "unresolved-bibref";
+pub const D_UNKNOWN_PLACEHOLDER: &str = "unknown-placeholder";
+pub const D_UNRESOLVED_LABEL: &str = "unresolved-label";
+pub const D_DUPLICATE_LABEL: &str = "duplicate-label";
+pub const D_UNRESOLVED_FOOTNOTE: &str = "unresolved-footnote";
+pub const D_UNRESOLVED_TERM: &str = "unresolved-term";
+pub const D_UNKNOWN_COLUMN: &str = "unknown-column";
+pub const D_INVALID_FORMULA: &str = "invalid-formula";
+pub const D_INVALID_DECIMALS: &str = "invalid-decimals";
+pub const D_UNCLOSED_CONTAINER: &str = "unclosed-container";
+pub const D_UNCLOSED_COMMENT: &str = "unclosed-comment";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: String,
+    pub message: String,
+    pub span: Span,
+    pub snippet: Option<String>,
+    pub hint: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics {
+    entries: Vec<Diagnostic>,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.format(false))
+    }
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, code: &str, message: String, span: Span) -> Self {
+        Self {
+            severity,
+            code: code.to_string(),
+            message,
+            span,
+            snippet: None,
+            hint: None,
+        }
+    }
+
+    pub fn error(code: &str, message: String, span: Span) -> Self {
+        Self::new(Severity::Error, code, message, span)
+    }
+
+    pub fn warning(code: &str, message: String, span: Span) -> Self {
+        Self::new(Severity::Warning, code, message, span)
+    }
+
+    pub fn set_hint(&mut self, hint: &str) {
+        self.hint = Some(hint.to_string());
+    }
+
+    /// Formats the diagnostic with the source line and a marker under the span
+    /// similar to the way rustc reports errors
+    pub fn format(&self, colored: bool) -> String {
+        let paint = |text: String, color: Color| {
+            if colored {
+                text.color(color).bold().to_string()
+            } else {
+                text
+            }
+        };
+        let severity_color = match self.severity {
+            Severity::Error => Color::Red,
+            Severity::Warning => Color::Yellow,
+            Severity::Info => Color::Blue,
+        };
+        let mut output = format!(
+            "{}: {}",
+            paint(format!("{}[{}]", self.severity, self.code), severity_color),
+            self.message
+        );
+        if self.span.line == 0 {
+            return output;
+        }
+        let line_number = format!("{}", self.span.line);
+        let gutter = " ".repeat(line_number.len());
+        let location = if let Some(file) = &self.span.path {
+            format!(
+                "{}:{}:{}",
+                file.to_string_lossy(),
+                self.span.line,
+                self.span.column
+            )
+        } else {
+            format!("{}:{}", self.span.line, self.span.column)
+        };
+        output += &format!(
+            "\n{}{} {}",
+            gutter,
+            paint("-->".to_string(), Color::Blue),
+            location
+        );
+
+        if let Some(snippet) = &self.snippet {
+            let bar = paint("|".to_string(), Color::Blue);
+            let marker_start = self.span.column - 1;
+            let mut marker_length = 0;
+            let mut bytes = 0;
+            for ch in snippet.chars().skip(marker_start) {
+                if bytes >= self.span.end - self.span.start {
+                    break;
+                }
+                bytes += ch.len_utf8();
+                marker_length += 1;
+            }
+            output += &format!(
+                "\n{} {}\n{} {} {}\n{} {} {}{}",
+                gutter,
+                bar,
+                paint(line_number, Color::Blue),
+                bar,
+                snippet,
+                gutter,
+                bar,
+                " ".repeat(marker_start),
+                paint("^".repeat(marker_length.max(1)), severity_color)
+            );
+        }
+        if let Some(hint) = &self.hint {
+            output += &format!(
+                "\n{} {} {}",
+                gutter,
+                paint("= hint:".to_string(), Color::Blue),
+                hint
+            );
+        }
+
+        output
+    }
+}
+
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, diagnostic: Diagnostic) {
+        self.entries.push(diagnostic)
+    }
+
+    /// moves the diagnostics of another list into this one
+    pub fn combine(&mut self, other: &mut Diagnostics) {
+        self.entries.append(&mut other.entries)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.entries.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, Diagnostic> {
+        self.entries.iter_mut()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// returns if any of the diagnostics is an error
+    pub fn has_errors(&self) -> bool {
+        self.entries.iter().any(|d| d.severity == Severity::Error)
+    }
+}
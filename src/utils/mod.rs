@@ -1 +1,3 @@
+pub mod csv;
+pub mod diagnostics;
 pub mod parsing;
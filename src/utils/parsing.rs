@@ -61,23 +61,35 @@ impl ParseError {
         }
     }
 
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+
+    pub fn get_message(&self) -> Option<String> {
+        self.message.clone()
+    }
+
     pub fn set_message(&mut self, message: &str) {
         self.message = Some(message.to_string());
     }
 
+    /// Returns the line and column of the error position starting at 1
+    /// with the column counted in characters
     pub fn get_position(&self, content: &str) -> Option<(usize, usize)> {
-        if content.len() <= self.index {
-            return None;
+        let mut line = 1;
+        let mut column = 1;
+        for (index, ch) in content.chars().enumerate() {
+            if index == self.index {
+                return Some((line, column));
+            }
+            if ch == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
         }
-        let split_content = content.split_at(self.index);
-        let line_number = split_content.0.lines().count() as usize;
 
-        if let Some(line) = split_content.0.lines().last() {
-            let inline_position = line.len();
-
-            Some((line_number, inline_position))
-        } else {
-            None
-        }
+        None
     }
 }
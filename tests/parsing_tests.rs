@@ -1,6 +1,8 @@
+use snekdown::elements::Block;
+use snekdown::format::html::ToHtml;
 use snekdown::parse;
-use snekdown::parser::elements::Block;
 use snekdown::Parser;
+use std::path::PathBuf;
 
 macro_rules! count_block_elements {
     ($document:expr, $filter:expr) => {
@@ -13,6 +15,22 @@ macro_rules! count_block_elements {
     };
 }
 
+/// creates an empty directory for test files that is unique to the test run
+fn create_temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "snekdown-{}-{}-{}",
+        name,
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    dir
+}
+
 #[test]
 fn it_inits() {
     let _ = Parser::new("".to_string(), None);
@@ -44,6 +62,100 @@ fn it_parses_tables() {
     )
 }
 
+#[test]
+fn it_aligns_table_columns() {
+    let document = parse!("|a|b|c|d|\n|:---|:-:|---:|---|\n|1|2|3|4|\n");
+    let html = document.to_html();
+    assert!(html.contains("<th style='text-align: left'>a</th>"));
+    assert!(html.contains("<td style='text-align: center'>2</td>"));
+    assert!(html.contains("<td style='text-align: right'>3</td>"));
+    assert!(html.contains("<td>4</td>"));
+}
+
+#[test]
+fn it_parses_table_spans() {
+    let document = parse!("|a||b|\n|c|d|e|\n|---|---|---|\n|1|2|3|\n|^^|4||\n|- x \\\n|- y|5|6|\n");
+    let html = document.to_html();
+    assert!(html.contains("<thead><tr><th colspan='2'>a</th><th>b</th></tr><tr><th>c</th>"));
+    assert!(html.contains("<td rowspan='2'>1</td>"));
+    assert!(html.contains("<tr><td colspan='2'>4</td></tr>"));
+    assert!(html.contains("<td><ul><li>x</li><li>y</li></ul></td><td>5</td>"));
+}
+
+#[test]
+fn it_renders_table_metadata() {
+    let document = parse!(
+        "See [@tab:prices]\n\n[caption=\"Prices\" label=tab:prices striped=false width=50%]\n|a|b|\n|---|---|\n|1|2|\n\n[[lot]]\n"
+    );
+    let html = document.to_html();
+    assert!(html.contains("<table class='unstriped' style='width: 50%;'><caption>"));
+    assert!(html.contains("<span class='figureNumber'>Table 1:</span> Prices</caption>"));
+    assert!(html.contains(">Table 1</a>"));
+    assert!(html.contains(">Table 1: Prices</a>"));
+}
+
+#[test]
+fn it_imports_csv_tables() {
+    let dir = create_temp_dir("csv-import");
+    std::fs::write(dir.join("data.csv"), "name,value\n\"a, b\",1.234\nc,2\n").unwrap();
+    std::fs::write(dir.join("literal.csv"), "a,b\n=1+1,x\n").unwrap();
+    std::fs::write(
+        dir.join("main.md"),
+        "<[data.csv][columns=\"value,1\" decimals=1]\n\n<[data.csv][decimals=-1]\n\n<[literal.csv]\n\n<[missing.csv]\nAfter\n",
+    )
+    .unwrap();
+    let mut parser = Parser::new_from_file(dir.join("main.md")).unwrap();
+    let document = parser.parse();
+    let html = document.to_html();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(html.contains("<thead><tr><th>value</th><th>name</th></tr></thead>"));
+    assert!(html.contains("<tr><td>1.2</td><td>a, b</td></tr><tr><td>2.0</td><td>c</td></tr>"));
+    assert!(html.contains("<tr><td>a, b</td><td>1.234</td></tr>"));
+    assert!(html.contains("<tr><td>=1+1</td><td>x</td></tr>"));
+    assert!(html.contains("After"));
+    assert!(parser.get_paths().contains(&dir.join("data.csv")));
+    let codes: Vec<&str> = document
+        .diagnostics
+        .iter()
+        .map(|d| d.code.as_str())
+        .collect();
+    assert!(codes.contains(&"invalid-decimals"));
+    assert_eq!(codes.iter().filter(|c| **c == "import-missing").count(), 1);
+}
+
+#[test]
+fn it_computes_table_formulas() {
+    let document = parse!(
+        "|a|b|c|\n|---|---|---|\n|1|2|=A2*B2|\n|3|4|=A3 * (B3 - 1)|\n|=sum(A)|=avg(B2:B3)|=sum(C2:C3) / 2|\n|=B2+B3|=A1+1|=C5|\n"
+    );
+    let html = document.to_html();
+    assert!(html.contains("<td>4</td><td>9</td></tr>"));
+    assert!(html.contains("<tr><td>10</td><td>3</td><td>5.5</td></tr>"));
+    let messages: Vec<String> = document
+        .diagnostics
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(messages.contains(&"invalid formula in cell B5: cell A1 is not a number".to_string()));
+    assert!(
+        messages.contains(&"invalid formula in cell C5: circular reference to cell C5".to_string())
+    );
+
+    let document =
+        parse!("[decimals=-2]\n|a|b|\n|---|---|\n|1|=A2/3|\n|2|=AAAAAAAAAAAAAAAAAAAA2|\n");
+    let html = document.to_html();
+    assert!(html.contains("<td>0.3333333333</td>"));
+    let messages: Vec<String> = document
+        .diagnostics
+        .iter()
+        .map(|d| d.message.clone())
+        .collect();
+    assert!(messages.contains(&"invalid number of decimals -2".to_string()));
+    assert!(messages.contains(
+        &"invalid formula in cell B3: invalid cell reference 'AAAAAAAAAAAAAAAAAAAA'".to_string()
+    ));
+}
+
 #[test]
 fn it_parses_paragraphs() {
     let document = parse!("**Bold***Italic*_Underline_`Monospace`^super^~strike~");
@@ -70,6 +182,38 @@ fn it_parses_lists() {
     )
 }
 
+#[test]
+fn it_parses_list_item_blocks() {
+    let document =
+        parse!("1. one\n   more\n\n   ```\n   - code\n   ```\n\n2. two\n   > quote\n3. three\n");
+    assert_eq!(document.elements.len(), 1);
+    let html = document.to_html();
+    assert!(html.contains("<li>one<br>more<br><div><code"));
+    assert!(html.contains("- code"));
+    assert!(html.contains("<li>two<div class='quote'><blockquote>quote</blockquote></div></li>"));
+    assert!(html.contains("<li>three</li>"));
+}
+
+#[test]
+fn it_numbers_ordered_lists() {
+    let document = parse!("1. a\n2. b\n```\ncode\n```\n4. c\n\nb) d\n   iv) e\n\nI) f\n");
+    let html = document.to_html();
+    assert!(html.contains("<ol><li>a</li><li>b</li></ol>"));
+    assert!(html.contains("<ol start='4'><li>c</li></ol>"));
+    assert!(html.contains("<ol start='2' type='a'><li>d<ol start='4' type='i'><li>e</li></ol>"));
+    assert!(html.contains("<ol type='I'><li>f</li></ol>"));
+
+    let html = parse!("42 is the answer.\n").to_html();
+    assert!(html.contains("<div class='paragraph'>42 is the answer.</div>"));
+    let html = parse!("Text\n\n2024 was a good year.\n").to_html();
+    assert!(html.contains("2024 was a good year."));
+    assert!(!html.contains("<ol"));
+    let html = parse!("I. e. the thing\n").to_html();
+    assert!(html.contains("<div class='paragraph'>I. e. the thing</div>"));
+    let html = parse!("A. Smith wrote this.\n").to_html();
+    assert!(html.contains("<div class='paragraph'>A. Smith wrote this.</div>"));
+}
+
 #[test]
 fn it_parses_code_blocks() {
     let document = parse!("```\ncode\n```\n```rust\ncode\n``````");
@@ -83,6 +227,63 @@ fn it_parses_code_blocks() {
     )
 }
 
+#[test]
+fn it_passes_raw_blocks_through() {
+    let document =
+        parse!("```raw html\n<b class=\"x\">raw</b>\n```\n```raw latex\n\\newpage\n```\n");
+    assert_eq!(
+        count_block_elements!(document, |e| if let Block::Raw(_) = e {
+            true
+        } else {
+            false
+        }),
+        2
+    );
+    let html = document.to_html();
+    assert!(html.contains("<b class=\"x\">raw</b>"));
+    assert!(!html.contains("newpage"));
+}
+
+#[test]
+fn it_strips_comments_and_annotations() {
+    let text = "// draft\nText /* secret */ shown{>>[author=Jane date=2020-05-01] Rephrase<<}\n/* removed\nblock */\n```\n// code\n```\n";
+    let document = parse!(text);
+    assert_eq!(document.elements.len(), 2);
+    let html = document.to_html();
+    assert!(html.contains("Text  shown"));
+    assert!(html.contains("// code"));
+    assert!(!html.contains("draft"));
+    assert!(!html.contains("secret"));
+    assert!(!html.contains("removed"));
+    assert!(!html.contains("Rephrase"));
+
+    let mut parser = Parser::new(text.to_string(), None);
+    parser.set_review_mode(true);
+    let html = parser.parse().to_html();
+    assert!(html.contains("<span class='annotationAuthor'>Jane</span>"));
+    assert!(html.contains("Rephrase</span>"));
+    assert!(!html.contains("secret"));
+
+    let document = parse!("[[set:review-mode]][value=true]\nA{>> note<<}\n/* open\nrest\n");
+    let html = document.to_html();
+    assert!(html.contains("note</span>"));
+    assert!(html.contains("/* open<br>rest"));
+    assert!(document
+        .diagnostics
+        .iter()
+        .any(|d| d.code == "unclosed-comment"));
+
+    let html = parse!("Text with /* and more\n").to_html();
+    assert!(html.contains("Text with /* and more"));
+    let html = parse!("Globs like src/*.rs are kept\n").to_html();
+    assert!(html.contains("Globs like src/*.rs are kept"));
+    let html = parse!("See lib/*/mod.rs /* note */\n").to_html();
+    assert!(html.contains("See lib/*/mod.rs </div>"));
+    let html = parse!("/*.rs files are kept\n```\ncode */\n```\n").to_html();
+    assert!(html.contains("/*.rs files are kept"));
+    assert!(html.contains("code */"));
+}
+
 #[test]
 fn it_parses_quotes() {
     let document = parse!("> quote\n\n[meta]> quote\n>hm");
@@ -95,3 +296,275 @@ fn it_parses_quotes() {
         2
     )
 }
+
+#[test]
+fn it_parses_nested_quotes() {
+    let document = parse!("[author=Me]> mail\n> > reply\n>\n> - item\n> ```\n> code\n> ```\n");
+    assert_eq!(document.elements.len(), 1);
+    let html = document.to_html();
+    assert!(html.contains(
+        "<blockquote>mail<div class='quote'><blockquote>reply</blockquote></div><ul><li>item</li></ul><div><code>"
+    ));
+    assert!(html.contains("<span class='metadata'>"));
+}
+
+#[test]
+fn it_parses_admonitions() {
+    let document = parse!(
+        "Text\n:::note\nA note\n:::\n\n:::warning Be *careful*\n- item\n:::tip\ninner\n:::\n:::\n\n:::danger\nunclosed\n"
+    );
+    let html = document.to_html();
+    assert!(html.contains("<div class='admonition note'><div class='admonitionTitle'>Note</div>"));
+    assert!(html.contains("<div class='admonitionTitle'>Be <i>careful</i></div><div class='admonitionContent'><ul><li>item</li></ul><div class='admonition tip'>"));
+    assert!(document
+        .diagnostics
+        .iter()
+        .any(|d| d.code == "unclosed-container"));
+}
+
+#[test]
+fn it_parses_details() {
+    let document =
+        parse!(":::details[open] Build *log*\n- output\n:::\n\n:::details\nhidden\n:::\n");
+    let html = document.to_html();
+    assert!(html.contains(
+        "<details class='details' open><summary>Build <i>log</i></summary><ul><li>output</li></ul></details>"
+    ));
+    assert!(html.contains("<details class='details'><summary>Details</summary>"));
+}
+
+#[test]
+fn it_parses_tabs() {
+    let document = parse!(
+        ":::tabs[label=os]\n:::tab Linux\n- apt\n:::\n\n:::tab Windows\n:::note\nnested\n:::\n:::\n:::\n\nAfter\n"
+    );
+    assert_eq!(document.elements.len(), 2);
+    let html = document.to_html();
+    assert!(html.contains("<div class='tabs' id='os'><input type='radio' class='tabInput' name='os' id='os&#x2D;1' aria-controls='os&#x2D;1&#x2D;pane' checked><label class='tabLabel' for='os&#x2D;1' id='os&#x2D;1&#x2D;label'>Linux</label><div class='tabPane' id='os&#x2D;1&#x2D;pane' role='region' aria-labelledby='os&#x2D;1&#x2D;label'><ul>"));
+    assert!(html.contains(
+        "id='os&#x2D;2&#x2D;label'>Windows</label><div class='tabPane' id='os&#x2D;2&#x2D;pane' role='region' aria-labelledby='os&#x2D;2&#x2D;label'><div class='admonition note'>"
+    ));
+}
+
+#[test]
+fn it_adds_snippets_of_imported_files() {
+    let dir = create_temp_dir("import-snippets");
+    std::fs::write(dir.join("part.md"), "Intro\nSee [@missing] here\n").unwrap();
+    std::fs::write(dir.join("main.md"), "<[part.md]\n").unwrap();
+    let document = Parser::new_from_file(dir.join("main.md")).unwrap().parse();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let diagnostic = document
+        .diagnostics
+        .iter()
+        .find(|d| d.code == "unresolved-label")
+        .unwrap();
+    assert_eq!(diagnostic.span.line, 2);
+    assert_eq!(diagnostic.snippet.as_deref(), Some("See [@missing] here"));
+}
+
+#[test]
+fn it_numbers_tabs_across_imports() {
+    let dir = create_temp_dir("tabs-import");
+    let tabs = ":::tabs\n:::tab A\na\n:::\n:::\n";
+    std::fs::write(dir.join("part.md"), tabs).unwrap();
+    std::fs::write(dir.join("main.md"), format!("{}\n<[part.md]\n", tabs)).unwrap();
+    let document = Parser::new_from_file(dir.join("main.md")).unwrap().parse();
+    let html = document.to_html();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(html.contains("name='tabs&#x2D;1'"));
+    assert!(html.contains("name='tabs&#x2D;2'"));
+}
+
+#[test]
+fn it_parses_columns() {
+    let document = parse!(
+        ":::columns[widths=\"1 20em\"]\n:::column\nLeft\n:::\n:::column\n- right\n:::\n:::\n\n:::columns\n:::column\na\n:::\n:::\n"
+    );
+    let html = document.to_html();
+    assert!(html.contains("<div class='columns' style='grid-template-columns: 1fr 20em;'><div class='column'><div class='paragraph'>Left</div></div><div class='column'><ul>"));
+    assert!(html.contains("style='grid-template-columns: repeat(1, 1fr);'"));
+}
+
+#[test]
+fn it_aligns_blocks() {
+    let document = parse!(
+        "|| centered\n|| lines\n\n:::right\n|a|\n|---|\n|1|\n:::\n\n:::justify\ntext\n:::\n"
+    );
+    assert_eq!(
+        count_block_elements!(document, |e| if let Block::Aligned(_) = e {
+            true
+        } else {
+            false
+        }),
+        3
+    );
+    let html = document.to_html();
+    assert!(html.contains(
+        "<div class='aligned center centered'><div class='paragraph'>centered<br>lines</div></div>"
+    ));
+    assert!(html.contains("<div class='aligned right'><div class='tableWrapper'>"));
+    assert!(html.contains("<div class='aligned justify'>"));
+}
+
+#[test]
+fn it_parses_math() {
+    let document = parse!("Costs $5 and $10, $x^2$\n$$\n\\frac{a}{b}\n$$\n");
+    assert_eq!(
+        count_block_elements!(document, |e| if let Block::Math(_) = e {
+            true
+        } else {
+            false
+        }),
+        1
+    );
+    let html = document.to_html();
+    assert!(html.contains("$5 and $10"));
+    assert!(html.contains("<msup><mi>x</mi><mn>2</mn></msup>"));
+    assert!(html.contains("<mfrac><mrow><mi>a</mi></mrow><mrow><mi>b</mi></mrow></mfrac>"));
+
+    let document = parse!("$$x$$");
+    assert!(matches!(document.elements.first(), Some(Block::Math(_))));
+    assert!(!document.to_html().contains('$'));
+    assert!(parse!("$$x").to_html().contains("$$x"));
+}
+
+#[test]
+fn it_parses_invalid_root_indices() {
+    let document = parse!("$$\\sqrt[a&b]{x}$$\n\n$$\\sqrt[}$$\n");
+    let html = document.to_html();
+    assert!(html.contains("<mroot>"));
+}
+
+#[test]
+fn it_resolves_cross_references() {
+    let document = parse!(
+        "# One\n## Two\n#[label=sec:three] Three\n![a](a.png)\n![b](b.png)[label=fig:b]\n\n[@sec:three] [@fig:b] [@missing]\n"
+    );
+    assert_eq!(document.labels.get_entry("sec:three").unwrap().number, "2");
+    // only labelled images consume a figure number when they aren't part of a figure
+    assert_eq!(document.labels.get_entry("fig:b").unwrap().number, "1");
+    let html = document.to_html();
+    assert!(html.contains(">Section 2</a>"));
+    assert!(html.contains(">Figure 1</a>"));
+    assert!(html.contains("<span class='figureNumber'>Figure 1:</span>"));
+    assert!(document
+        .diagnostics
+        .iter()
+        .any(|d| d.code == "unresolved-label"));
+}
+
+#[test]
+fn it_numbers_figures() {
+    let document = parse!(
+        "[[lof]]\nText\n![a](a.png)\n: First\n\n|a|\n|---|\n|b|\n: Table\n\n![b](b.png)\n:[label=fig:b] Second\n\n[@fig:b]\n"
+    );
+    assert_eq!(
+        count_block_elements!(document, |e| if let Block::Figure(_) = e {
+            true
+        } else {
+            false
+        }),
+        3
+    );
+    assert_eq!(document.labels.get_entry("fig:b").unwrap().number, "2");
+    let html = document.to_html();
+    assert!(html.contains("<a href='#figure&#x2D;1'>Figure 1: First</a>"));
+    assert!(html.contains("Table 1:</span> Table"));
+    assert!(html.contains(">Figure 2</a>"));
+}
+
+#[test]
+fn it_parses_footnotes() {
+    let document = parse!(
+        "Text[*b] and[*a] again[*b] [*missing]\n\n[*a]: First **note**\n[*b]: Second note\n"
+    );
+    let html = document.to_html();
+    assert!(html.contains("<li id='fn-b' value='1'>"));
+    assert!(html.contains("<li id='fn-a' value='2'>First <b>note</b>"));
+    assert!(html.contains("href='#fnref-b-2'"));
+    assert!(document
+        .diagnostics
+        .iter()
+        .any(|d| d.code == "unresolved-footnote"));
+}
+
+#[test]
+fn it_parses_glossary_entries() {
+    let document = parse!(
+        "[~API] and [~API]\n\n[~API]:[long=\"Application Interface\"]\n[~b]:[long=\"Unused\"]\n\n[[glossary]]\n"
+    );
+    let html = document.to_html();
+    assert!(html.contains(">Application Interface (API)</a> and "));
+    assert!(html.contains("'>API</a>"));
+    assert!(html.contains("<dt id='glossary-API'>API</dt>"));
+    assert!(!html.contains("Unused</dd>"));
+}
+
+#[test]
+fn it_creates_an_index() {
+    let document = parse!("Text[#zebra] and[#parser!errors] more[#Parser]\n\n[[index]]\n");
+    let html = document.to_html();
+    assert!(html.contains("<span class='indexMarker' id='index-2'></span>"));
+    assert!(html.contains(
+        "<div class='indexLetter'>P</div><ul><li>parser, <a href='#index-3'>1</a><ul><li>errors, <a href='#index-2'>1</a></li>"
+    ));
+    assert!(html.find("indexLetter'>P").unwrap() < html.find("indexLetter'>Z").unwrap());
+
+    let html = parse!("Text[#zebra] and[#parser]\n").to_html();
+    assert!(html.contains("<span class='indexMarker' id='index-2'></span>"));
+}
+
+#[test]
+fn it_assigns_spans() {
+    let document = parse!("```\nä\n```\n```rust\ncode\n```");
+    let span = document.elements.last().unwrap().get_span();
+    assert_eq!(span.line, 4);
+    assert_eq!(span.column, 1);
+    assert_eq!(span.start, 11);
+    assert_eq!(span.end, 27);
+}
+
+#[test]
+fn it_locates_nested_lines() {
+    let document =
+        parse!("> first line\n> second [[ä]] x\n\n- item\n  more [[b]]\n\n| a \\\n|  c [[d]]\n");
+    let spans: Vec<(usize, usize, usize, usize)> = document
+        .diagnostics
+        .iter()
+        .map(|d| (d.span.line, d.span.column, d.span.start, d.span.end))
+        .collect();
+    assert_eq!(spans, vec![(2, 10, 22, 28), (5, 8, 46, 51), (8, 6, 64, 69)]);
+}
+
+#[test]
+fn it_reports_diagnostics() {
+    let document = parse!("<[missing.md]\n\nA reference[^nothing] and [[unknown]]");
+    let codes: Vec<&str> = document
+        .diagnostics
+        .iter()
+        .map(|d| d.code.as_str())
+        .collect();
+    assert!(codes.contains(&"import-missing"));
+    assert!(codes.contains(&"unresolved-bibref"));
+    assert!(codes.contains(&"unknown-placeholder"));
+    assert!(document.diagnostics.has_errors());
+}
+
+#[test]
+fn it_recovers_from_errors() {
+    let document = parse!("||a\n- :");
+    assert!(document.diagnostics.has_errors());
+    assert_eq!(document.elements.len(), 2);
+
+    let document = parse!("Text\n[meta");
+    assert_eq!(document.elements.len(), 2);
+}
+
+#[test]
+fn it_formats_diagnostics() {
+    let document = parse!("Hällo [[unknown]] text");
+    let report = document.diagnostics.iter().next().unwrap().format(false);
+    assert!(report.contains("1:7"));
+    assert!(report.contains("1 | Hällo [[unknown]] text"));
+    assert!(report.contains("  |       ^^^^^^^^^^^\n"));
+}